@@ -0,0 +1,157 @@
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Result as FmtResult;
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum TypeOfNumber {
+    Unknown,
+    International,
+    National,
+    NetworkSpecific,
+    Subscriber,
+    Alphanumeric,
+    Abbreviated,
+    Reserved,
+}
+
+impl From<u8> for TypeOfNumber {
+    fn from(value: u8) -> Self {
+        match value & 0x07 {
+            0 => Self::Unknown,
+            1 => Self::International,
+            2 => Self::National,
+            3 => Self::NetworkSpecific,
+            4 => Self::Subscriber,
+            5 => Self::Alphanumeric,
+            6 => Self::Abbreviated,
+            _ => Self::Reserved,
+        }
+    }
+}
+
+impl From<TypeOfNumber> for u8 {
+    fn from(value: TypeOfNumber) -> Self {
+        match value {
+            TypeOfNumber::Unknown => 0,
+            TypeOfNumber::International => 1,
+            TypeOfNumber::National => 2,
+            TypeOfNumber::NetworkSpecific => 3,
+            TypeOfNumber::Subscriber => 4,
+            TypeOfNumber::Alphanumeric => 5,
+            TypeOfNumber::Abbreviated => 6,
+            TypeOfNumber::Reserved => 7,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum NumberingPlan {
+    Unknown,
+    Isdn,
+    Data,
+    Telex,
+    National,
+    Private,
+    Ermes,
+    Reserved(u8),
+}
+
+impl From<u8> for NumberingPlan {
+    fn from(value: u8) -> Self {
+        match value & 0x0F {
+            0 => Self::Unknown,
+            1 => Self::Isdn,
+            3 => Self::Data,
+            4 => Self::Telex,
+            8 => Self::National,
+            9 => Self::Private,
+            10 => Self::Ermes,
+            value => Self::Reserved(value),
+        }
+    }
+}
+
+impl From<NumberingPlan> for u8 {
+    fn from(value: NumberingPlan) -> Self {
+        match value {
+            NumberingPlan::Unknown => 0,
+            NumberingPlan::Isdn => 1,
+            NumberingPlan::Data => 3,
+            NumberingPlan::Telex => 4,
+            NumberingPlan::National => 8,
+            NumberingPlan::Private => 9,
+            NumberingPlan::Ermes => 10,
+            NumberingPlan::Reserved(value) => value & 0x0F,
+        }
+    }
+}
+
+/// Phone number or alphanumeric sender name together with its type of address.
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct Address {
+    number: String,
+    type_of_number: TypeOfNumber,
+    numbering_plan: NumberingPlan,
+}
+
+impl Address {
+    pub fn new(number: &str, type_of_number: TypeOfNumber, numbering_plan: NumberingPlan) -> Self {
+        Self {
+            number: number.into(),
+            type_of_number,
+            numbering_plan,
+        }
+    }
+
+    /// Creates address from number and type of address octet (`<tooa>`/`<toda>` in AT commands).
+    pub fn with_type(number: &str, type_of_address: u8) -> Self {
+        let type_of_number = TypeOfNumber::from(type_of_address >> 4);
+        let number = match type_of_number {
+            TypeOfNumber::International => number.trim_start_matches('+'),
+            _ => number,
+        };
+
+        Self::new(number, type_of_number, NumberingPlan::from(type_of_address))
+    }
+
+    /// Guesses type of address from text: leading `+` means international number, digits only
+    /// means unknown number and anything else is alphanumeric.
+    pub fn parse(text: &str) -> Self {
+        if let Some(number) = text.strip_prefix('+') {
+            Self::new(number, TypeOfNumber::International, NumberingPlan::Isdn)
+        } else if text.chars().all(|ch| matches!(ch, '0'..='9' | '*' | '#')) {
+            Self::new(text, TypeOfNumber::Unknown, NumberingPlan::Isdn)
+        } else {
+            Self::new(text, TypeOfNumber::Alphanumeric, NumberingPlan::Unknown)
+        }
+    }
+
+    pub fn number(&self) -> &str {
+        &self.number
+    }
+
+    pub fn type_of_number(&self) -> TypeOfNumber {
+        self.type_of_number
+    }
+
+    pub fn numbering_plan(&self) -> NumberingPlan {
+        self.numbering_plan
+    }
+
+    pub fn type_of_address(&self) -> u8 {
+        0x80 | u8::from(self.type_of_number) << 4 | u8::from(self.numbering_plan)
+    }
+
+    pub fn is_alphanumeric(&self) -> bool {
+        self.type_of_number == TypeOfNumber::Alphanumeric
+    }
+}
+
+impl Display for Address {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self.type_of_number {
+            TypeOfNumber::International => write!(f, "+{}", self.number),
+            _ => write!(f, "{}", self.number),
+        }
+    }
+}
@@ -0,0 +1,289 @@
+use rusqlite::params;
+use rusqlite::types::Type;
+use rusqlite::Connection;
+use rusqlite::Error as SqliteError;
+use rusqlite::OptionalExtension;
+use rusqlite::Row;
+use sha2::Digest;
+use sha2::Sha256;
+use sim800_reader::address::Address;
+use sim800_reader::concat::SmsMessage;
+use sim800_reader::parser::SmsMessageStatus;
+use sim800_reader::pdu::encode_hex;
+use std::path::Path;
+use time::format_description::well_known::Rfc3339;
+use time::Duration;
+use time::OffsetDateTime;
+use time::UtcOffset;
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS messages (
+        id INTEGER PRIMARY KEY,
+        hash TEXT NOT NULL UNIQUE,
+        status INTEGER NOT NULL,
+        address TEXT NOT NULL,
+        type_of_address INTEGER NOT NULL,
+        address_text TEXT,
+        timestamp INTEGER,
+        utc_offset INTEGER,
+        text TEXT NOT NULL,
+        archived INTEGER NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS messages_address ON messages (address);
+    CREATE INDEX IF NOT EXISTS messages_timestamp ON messages (timestamp);
+";
+
+const COLUMNS: &str = "status, address, type_of_address, address_text, timestamp, utc_offset, text";
+
+/// Conditions to search archived messages, message found if all given conditions match.
+#[derive(Debug, Default)]
+pub struct Query<'a> {
+    pub text: Option<&'a str>,
+    pub from: Option<&'a str>,
+    pub newer_than: Option<u32>,
+}
+
+/// Local SQLite archive of complete messages. Messages identified by hash of sender, time stamp
+/// and text, so the same message read again or from other storage is stored once.
+#[derive(Debug)]
+pub struct Archive {
+    connection: Connection,
+}
+
+impl Archive {
+    pub fn open<P>(path: P) -> Result<Self, SqliteError>
+    where
+        P: AsRef<Path>,
+    {
+        let connection = Connection::open(path)?;
+
+        connection.execute_batch(SCHEMA)?;
+
+        Ok(Self { connection })
+    }
+
+    /// Stores complete message, returns `false` if message already archived or incomplete.
+    pub fn store(&self, message: &SmsMessage) -> Result<bool, SqliteError> {
+        if !message.is_complete() {
+            return Ok(false);
+        }
+
+        let count = self.connection.execute(
+            "INSERT OR IGNORE INTO messages (hash, status, address, type_of_address,
+                address_text, timestamp, utc_offset, text, archived)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                message_hash(message),
+                status_code(&message.status),
+                message.address.number(),
+                message.address.type_of_address(),
+                message.address_text,
+                message.timestamp.map(OffsetDateTime::unix_timestamp),
+                message
+                    .timestamp
+                    .map(|timestamp| timestamp.offset().whole_seconds()),
+                message.text,
+                OffsetDateTime::now_utc().unix_timestamp(),
+            ],
+        )?;
+
+        Ok(count > 0)
+    }
+
+    /// Stores all complete messages, returns number of new messages.
+    pub fn store_all(&self, messages: &[SmsMessage]) -> Result<usize, SqliteError> {
+        let mut result = 0;
+
+        for message in messages {
+            if self.store(message)? {
+                result += 1;
+            }
+        }
+
+        Ok(result)
+    }
+
+    pub fn contains(&self, message: &SmsMessage) -> Result<bool, SqliteError> {
+        let id: Option<i64> = self
+            .connection
+            .query_row(
+                "SELECT id FROM messages WHERE hash = ?1",
+                [message_hash(message)],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        Ok(id.is_some())
+    }
+
+    /// Returns archived messages matching query ordered by time stamp. Archived messages have no
+    /// storage indices.
+    pub fn search(&self, query: &Query) -> Result<Vec<SmsMessage>, SqliteError> {
+        let pattern = query.text.map(|text| {
+            format!(
+                "%{}%",
+                text.replace('\\', "\\\\")
+                    .replace('%', "\\%")
+                    .replace('_', "\\_")
+            )
+        });
+        let from = query
+            .from
+            .map(|from| Address::parse(from).number().to_string());
+        let since = query
+            .newer_than
+            .map(|days| (OffsetDateTime::now_utc() - Duration::days(days.into())).unix_timestamp());
+        let mut statement = self.connection.prepare(&format!(
+            "SELECT {} FROM messages
+            WHERE (?1 IS NULL OR text LIKE ?1 ESCAPE '\\')
+                AND (?2 IS NULL OR address = ?2 COLLATE NOCASE)
+                AND (?3 IS NULL OR timestamp >= ?3)
+            ORDER BY timestamp, id",
+            COLUMNS
+        ))?;
+        let rows = statement.query_map(params![pattern, from, since], read_message)?;
+
+        rows.collect()
+    }
+}
+
+fn read_message(row: &Row) -> Result<SmsMessage, SqliteError> {
+    let address: String = row.get(1)?;
+    let timestamp: Option<i64> = row.get(4)?;
+    let utc_offset: Option<i32> = row.get(5)?;
+    let timestamp = match (timestamp, utc_offset) {
+        (Some(timestamp), Some(utc_offset)) => OffsetDateTime::from_unix_timestamp(timestamp)
+            .ok()
+            .zip(UtcOffset::from_whole_seconds(utc_offset).ok())
+            .map(|(timestamp, offset)| timestamp.to_offset(offset)),
+        _ => None,
+    };
+
+    Ok(SmsMessage {
+        indices: Vec::new(),
+        status: SmsMessageStatus::try_from(row.get::<_, u8>(0)?).map_err(|error| {
+            SqliteError::FromSqlConversionFailure(0, Type::Integer, error.into())
+        })?,
+        address: Address::with_type(&address, row.get(2)?),
+        address_text: row.get(3)?,
+        timestamp,
+        text: row.get(6)?,
+        total: 1,
+        missing: Vec::new(),
+    })
+}
+
+fn status_code(status: &SmsMessageStatus) -> u8 {
+    match status {
+        SmsMessageStatus::ReceivedUnread => 0,
+        SmsMessageStatus::ReceivedRead => 1,
+        SmsMessageStatus::StoredUnsent => 2,
+        SmsMessageStatus::StoredSent => 3,
+    }
+}
+
+/// Returns SHA-256 hash of sender, time stamp and text as hexadecimal string.
+fn message_hash(message: &SmsMessage) -> String {
+    let timestamp = message
+        .timestamp
+        .and_then(|timestamp| timestamp.format(&Rfc3339).ok())
+        .unwrap_or_default();
+    let mut hasher = Sha256::new();
+
+    hasher.update(message.address.to_string());
+    hasher.update([0]);
+    hasher.update(timestamp);
+    hasher.update([0]);
+    hasher.update(&message.text);
+
+    encode_hex(&hasher.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::datetime;
+
+    fn message(address: &str, timestamp: OffsetDateTime, text: &str) -> SmsMessage {
+        SmsMessage {
+            indices: vec![1],
+            status: SmsMessageStatus::ReceivedUnread,
+            address: Address::parse(address),
+            address_text: None,
+            timestamp: Some(timestamp),
+            text: text.into(),
+            total: 1,
+            missing: Vec::new(),
+        }
+    }
+
+    fn archive() -> Archive {
+        Archive::open(":memory:").unwrap()
+    }
+
+    #[test]
+    fn stores_message_once() {
+        let archive = archive();
+        let timestamp = datetime!(2023-12-31 23:30:00 +2);
+        let first = message("+123", timestamp, "hello");
+        let mut again = first.clone();
+
+        // Hash covers sender, time stamp and text only.
+        again.indices = vec![5, 6];
+        again.status = SmsMessageStatus::ReceivedRead;
+
+        assert!(archive.store(&first).unwrap());
+        assert!(!archive.store(&first).unwrap());
+        assert!(!archive.store(&again).unwrap());
+        assert_eq!(
+            archive
+                .store_all(&[
+                    again,
+                    message("+456", timestamp, "hello"),
+                    message("+123", timestamp + Duration::seconds(1), "hello"),
+                    message("+123", timestamp, "hello!"),
+                ])
+                .unwrap(),
+            3
+        );
+        assert_eq!(archive.search(&Query::default()).unwrap().len(), 4);
+    }
+
+    #[test]
+    fn skips_incomplete_message() {
+        let archive = archive();
+        let mut message = message("+123", datetime!(2023-12-31 23:30:00 +2), "hel");
+
+        message.total = 2;
+        message.missing = vec![2];
+
+        assert!(!archive.store(&message).unwrap());
+        assert!(!archive.contains(&message).unwrap());
+    }
+
+    #[test]
+    fn finds_archived_message() {
+        let archive = archive();
+        let timestamp = datetime!(2023-12-31 23:30:00 +2);
+        let stored = message("+123", timestamp, "hello");
+
+        assert!(!archive.contains(&stored).unwrap());
+
+        archive.store(&stored).unwrap();
+
+        assert!(archive.contains(&stored).unwrap());
+        assert!(!archive
+            .contains(&message("+123", timestamp, "other"))
+            .unwrap());
+        assert!(!archive
+            .contains(&message("+456", timestamp, "hello"))
+            .unwrap());
+
+        let found = archive.search(&Query::default()).unwrap();
+
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].timestamp, Some(timestamp));
+        assert_eq!(found[0].timestamp.unwrap().offset(), timestamp.offset());
+        assert!(archive.contains(&found[0]).unwrap());
+    }
+}
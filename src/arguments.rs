@@ -1,47 +1,294 @@
 use clap::Parser;
+use clap::Subcommand;
+use clap::ValueEnum;
+use sim800_reader::parser::CharacterSet;
+use std::path::PathBuf;
 
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
 pub struct Arguments {
-    /// Port name or path.
+    /// Port name or path, `tcp://<HOST>:<PORT>` to connect remote port over TCP,
+    /// `replay://<FILE>` to replay recorded transcript. Required for all commands except archive.
     #[arg(short, long)]
-    serial_port: String,
+    serial_port: Option<String>,
 
     /// Port baud rate.
     #[arg(short, long, default_value_t = 115_200)]
     baud_rate: u32,
 
-    /// Port read timeout.
-    #[arg(short, long, default_value_t = 30)]
+    /// Fix module baud rate (`AT+IPR`) to port baud rate instead of autobaud.
+    #[arg(long)]
+    fix_baud_rate: bool,
+
+    /// Time in seconds to wait until module ready after power on.
+    #[arg(long, default_value_t = 20)]
+    ready_timeout: u64,
+
+    /// Command response timeout in seconds.
+    #[arg(short, long, default_value_t = 10)]
     timeout: u64,
 
+    /// Time zone used to show message time stamps.
+    #[arg(short = 'z', long, value_enum, default_value_t = TimeZone::Original)]
+    time_zone: TimeZone,
+
+    /// Output format of listed and received messages.
+    #[arg(short, long, value_enum, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+
+    /// SQLite archive file, listed and received messages are stored in it.
+    #[arg(short, long)]
+    archive: Option<PathBuf>,
+
+    /// Record timestamped transcript of port traffic to file.
+    #[arg(long)]
+    record: Option<PathBuf>,
+
+    /// Disable command echo (`ATE0`) after connecting.
+    #[arg(long)]
+    no_echo: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy)]
+pub enum TimeZone {
+    /// Local time zone of this computer.
+    Local,
+    /// Coordinated universal time.
+    Utc,
+    /// Time zone reported by service center.
+    Original,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy)]
+pub enum OutputFormat {
+    /// Human readable line per message.
+    Text,
+    /// JSON array of message objects.
+    Json,
+    /// JSON object per line.
+    Ndjson,
+    /// Comma separated values with header.
+    Csv,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageStatus {
+    /// Received unread message.
+    Unread,
+    /// Received read message.
+    Read,
+    /// Stored unsent message.
+    Unsent,
+    /// Stored sent message.
+    Sent,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BulkDelete {
+    /// Read messages (`AT+CMGD=1,1`).
+    Read,
+    /// Read and sent messages (`AT+CMGD=1,2`).
+    ReadSent,
+    /// Read, sent and unsent messages (`AT+CMGD=1,3`).
+    ReadSentUnsent,
+    /// All messages (`AT+CMGD=1,4`).
+    All,
+    /// Unread messages (`AT+CMGDA`).
+    Unread,
+    /// Sent messages (`AT+CMGDA`).
+    Sent,
+    /// Unsent messages (`AT+CMGDA`).
+    Unsent,
+    /// Read and unread messages (`AT+CMGDA`).
+    Inbox,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    #[command(flatten)]
+    Module(ModuleCommand),
+    /// Show messages stored in archive.
+    Archive {
+        #[command(subcommand)]
+        command: ArchiveCommand,
+    },
+}
+
+// Commands requiring connected module, doc comment would replace application description.
+#[derive(Subcommand, Debug)]
+pub enum ModuleCommand {
+    /// Show network registration, operator and signal quality.
+    Status,
+    /// Manage SMS messages.
+    Sms {
+        /// Use PDU message format instead of text.
+        #[arg(short, long, global = true, default_value_t = false)]
+        pdu_mode: bool,
+
+        /// TE character set for text mode: GSM, IRA or UCS2.
+        #[arg(short, long, global = true, default_value_t = CharacterSet::Ucs2)]
+        character_set: CharacterSet,
+
+        #[command(subcommand)]
+        command: SmsCommand,
+    },
+    /// Make voice call and hang up after given time or when call ends.
+    Call {
+        /// Phone number to call.
+        number: String,
+
+        /// Call duration in seconds.
+        #[arg(short, long, default_value_t = 20)]
+        duration: u64,
+    },
+    /// Send USSD request and show reply.
+    Ussd {
+        /// USSD request, like `*100#`.
+        request: String,
+    },
+    /// Show module state and battery charge.
+    Info,
+    /// Send AT commands and show parsed responses.
+    Raw {
+        /// AT commands to send in order.
+        #[arg(required = true)]
+        commands: Vec<String>,
+    },
+    /// Type AT commands interactively and show received text with parsed responses.
+    Terminal {
+        /// File to load and save command history.
+        #[arg(long)]
+        history: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ArchiveCommand {
+    /// Show all archived messages.
+    Export,
+    /// Show archived messages matching all given filters.
+    Search {
+        /// Text contained in message.
+        text: Option<String>,
+
+        /// Messages from given sender or to given recipient.
+        #[arg(long)]
+        from: Option<String>,
+
+        /// Messages not older than given number of days.
+        #[arg(long)]
+        newer_than: Option<u32>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum SmsCommand {
     /// List all SMS messages.
-    #[arg(short, long, default_value_t = false)]
-    list_messages: bool,
+    List,
+    /// Read SMS message with given index.
+    Read {
+        /// Message index in storage.
+        index: u16,
+    },
+    /// Send SMS message.
+    Send {
+        /// Recipient phone number.
+        address: String,
+
+        /// Message text.
+        text: String,
+
+        /// Request delivery status report and wait for it until timeout.
+        #[arg(short = 'r', long)]
+        status_report: bool,
+    },
+    /// Remove SMS messages selected by index, status, sender or age, read and sent messages
+    /// removed if nothing selected.
+    Delete {
+        /// Indices of message parts to remove.
+        indices: Vec<u16>,
+
+        /// Remove messages with given status, can be repeated.
+        #[arg(long, value_enum)]
+        status: Vec<MessageStatus>,
+
+        /// Remove messages from given sender or to given recipient.
+        #[arg(long)]
+        from: Option<String>,
+
+        /// Remove messages older than given number of days.
+        #[arg(long)]
+        older_than: Option<u32>,
+
+        /// Remove messages in bulk with single command.
+        #[arg(long, value_enum, conflicts_with_all = ["indices", "status", "from", "older_than"])]
+        bulk: Option<BulkDelete>,
+
+        /// Show messages to remove without removing them.
+        #[arg(short = 'n', long)]
+        dry_run: bool,
+
+        /// Remove messages missing in archive.
+        #[arg(long)]
+        force: bool,
+    },
+    /// Wait for incoming SMS messages until interrupted.
+    Watch {
+        /// Remove messages from module after processing.
+        #[arg(short, long, default_value_t = false)]
+        delete: bool,
 
-    /// Remove all SMS messages.
-    #[arg(short, long, default_value_t = false)]
-    delete_messages: bool,
+        /// Append messages to file instead of standard output.
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
 }
 
 impl Arguments {
-    pub fn serial_port(&self) -> &str {
-        &self.serial_port
+    pub fn serial_port(&self) -> Option<&str> {
+        self.serial_port.as_deref()
     }
 
     pub fn baud_rate(&self) -> u32 {
         self.baud_rate
     }
 
+    pub fn fix_baud_rate(&self) -> bool {
+        self.fix_baud_rate
+    }
+
+    pub fn ready_timeout(&self) -> u64 {
+        self.ready_timeout
+    }
+
     pub fn timeout(&self) -> u64 {
         self.timeout
     }
 
-    pub fn list_messages(&self) -> bool {
-        self.list_messages
+    pub fn time_zone(&self) -> TimeZone {
+        self.time_zone
+    }
+
+    pub fn format(&self) -> OutputFormat {
+        self.format
+    }
+
+    pub fn archive(&self) -> Option<&PathBuf> {
+        self.archive.as_ref()
+    }
+
+    pub fn record(&self) -> Option<&PathBuf> {
+        self.record.as_ref()
+    }
+
+    pub fn no_echo(&self) -> bool {
+        self.no_echo
     }
 
-    pub fn delete_messages(&self) -> bool {
-        self.delete_messages
+    pub fn command(&self) -> &Command {
+        &self.command
     }
 }
@@ -0,0 +1,94 @@
+use clap::Parser;
+use sim800_reader::parser::SmsMessageStatus;
+use sim800_reader::simulator::Fault;
+use sim800_reader::simulator::Simulator;
+use sim800_reader::simulator::SimulatorHandle;
+use sim800_reader::transport::PtyTransport;
+use std::error::Error;
+use std::io::stdin;
+use std::io::BufRead;
+use std::time::Duration;
+use time::OffsetDateTime;
+
+/// Pseudo terminal read timeout, limits time to stop simulator thread.
+const READ_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// SIM800 module simulator on pseudo terminal. Commands are read from standard input:
+/// `sms <ADDRESS> <TEXT>`, `urc <LINE>`, `fault garbled|timeout|error <CODE>`,
+/// `register on|off`, `busy on|off` and `quit`.
+#[derive(Parser, Debug)]
+#[command(version, about, long_about = None)]
+struct Arguments {
+    /// Stored received message, format: <ADDRESS>:<TEXT>.
+    #[arg(short, long)]
+    message: Vec<String>,
+}
+
+fn execute(handle: &SimulatorHandle, line: &str) -> Result<bool, Box<dyn Error>> {
+    let (command, argument) = line.split_once(' ').unwrap_or((line, ""));
+
+    match (command, argument.trim()) {
+        ("sms", argument) => match argument.split_once(' ') {
+            Some((address, text)) => {
+                handle.receive_message(address, OffsetDateTime::now_utc(), text)?
+            }
+            None => println!("Usage: sms <ADDRESS> <TEXT>"),
+        },
+        ("urc", argument) => handle.emit(argument)?,
+        ("fault", "garbled") => handle.simulator().inject_fault(Fault::Garbled),
+        ("fault", "timeout") => handle.simulator().inject_fault(Fault::Timeout),
+        ("fault", argument) => match argument.strip_prefix("error ").map(str::parse) {
+            Some(Ok(code)) => handle.simulator().inject_fault(Fault::ServiceError(code)),
+            _ => println!("Usage: fault garbled|timeout|error <CODE>"),
+        },
+        ("register", "on") => handle.simulator().set_registered(true),
+        ("register", "off") => handle.simulator().set_registered(false),
+        ("busy", "on") => handle.simulator().set_busy(true),
+        ("busy", "off") => handle.simulator().set_busy(false),
+        ("quit", _) => return Ok(false),
+        ("", _) => {}
+        _ => println!("Unknown command: {}", line),
+    }
+
+    Ok(true)
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    env_logger::init();
+
+    let arguments = Arguments::parse();
+    let mut simulator = Simulator::new();
+
+    for message in &arguments.message {
+        match message.split_once(':') {
+            Some((address, text)) => {
+                simulator.add_message(
+                    SmsMessageStatus::ReceivedUnread,
+                    address,
+                    OffsetDateTime::now_utc(),
+                    text,
+                );
+            }
+            None => return Err(format!("Invalid message: {}", message).into()),
+        }
+    }
+
+    let transport = PtyTransport::open(READ_TIMEOUT)?;
+
+    println!(
+        "Simulator listening on {}.",
+        transport.slave_path().display()
+    );
+
+    let handle = simulator.spawn(Box::new(transport))?;
+
+    for line in stdin().lock().lines() {
+        if !execute(&handle, line?.trim())? {
+            break;
+        }
+    }
+
+    handle.join()?;
+
+    Ok(())
+}
@@ -0,0 +1,892 @@
+use crate::archive::Archive;
+use crate::archive::Query;
+use crate::arguments::BulkDelete;
+use crate::delete;
+use crate::delete::Plan;
+use crate::delete::Selection;
+use crate::output::Output;
+use crate::watch;
+use sim800_reader::concat::reassemble;
+use sim800_reader::concat::SmsMessage;
+use sim800_reader::parser::BatteryChargeStatus;
+use sim800_reader::parser::CharacterSet;
+use sim800_reader::parser::NetworkRegistrationStatus;
+use sim800_reader::parser::PhoneActivityStatus;
+use sim800_reader::parser::Response;
+use sim800_reader::parser::SignalQualityRssi;
+use sim800_reader::sim800::DeliveryState;
+use sim800_reader::sim800::Sim800;
+use sim800_reader::sim800::Sim800Error;
+use std::error::Error;
+use std::fs::OpenOptions;
+use std::io::stdout;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::mpsc::RecvTimeoutError;
+use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
+use time::OffsetDateTime;
+
+/// Exit code when module not registered in network.
+pub const EXIT_NOT_REGISTERED: u8 = 3;
+
+/// Exit code when module replied with error result code.
+pub const EXIT_MODEM_ERROR: u8 = 4;
+
+/// Exit code when module did not reply in time.
+pub const EXIT_TIMEOUT: u8 = 5;
+
+/// Exit code when request accepted but not completed: message not sent or delivered, call not
+/// answered or USSD request rejected.
+pub const EXIT_NOT_COMPLETED: u8 = 6;
+
+/// Returns command listing all messages, PDU mode accepts only numeric status.
+fn list_command(pdu_mode: bool) -> &'static str {
+    if pdu_mode {
+        "AT+CMGL=4"
+    } else {
+        r#"AT+CMGL="ALL""#
+    }
+}
+
+/// Returns command listing all messages without marking unread messages as read.
+fn list_keep_status_command(pdu_mode: bool) -> &'static str {
+    if pdu_mode {
+        "AT+CMGL=4,1"
+    } else {
+        r#"AT+CMGL="ALL",1"#
+    }
+}
+
+/// Returns exit code for error returned by command.
+pub fn exit_code(error: &(dyn Error + 'static)) -> ExitCode {
+    match error.downcast_ref::<Sim800Error>() {
+        Some(Sim800Error::Modem(_)) => ExitCode::from(EXIT_MODEM_ERROR),
+        Some(Sim800Error::Timeout(_)) => ExitCode::from(EXIT_TIMEOUT),
+        _ => ExitCode::FAILURE,
+    }
+}
+
+/// Prints status message, messages go to standard error when output intended for scripts.
+fn notice(output: &Output, message: &str) {
+    if output.is_structured() {
+        eprintln!("{}", message);
+    } else {
+        println!("{}", message);
+    }
+}
+
+fn is_registered(sim800: &mut Sim800) -> Result<bool, Box<dyn Error>> {
+    Ok(matches!(
+        sim800.send_one(r#"AT+CREG?"#)?,
+        Some(Response::NetworkRegistration {
+            status: NetworkRegistrationStatus::Registered
+                | NetworkRegistrationStatus::RegisteredRoaming,
+            ..
+        })
+    ))
+}
+
+pub fn status(sim800: &mut Sim800) -> Result<ExitCode, Box<dyn Error>> {
+    let registration = match sim800.send_one(r#"AT+CREG?"#)? {
+        Some(Response::NetworkRegistration { status, .. }) => status,
+        _ => return Err(Sim800Error::MissingReply(r#"AT+CREG?"#.into()).into()),
+    };
+    let registered = match registration {
+        NetworkRegistrationStatus::Registered => "registered",
+        NetworkRegistrationStatus::RegisteredRoaming => "registered, roaming",
+        NetworkRegistrationStatus::SearchingOperator => "searching operator",
+        NetworkRegistrationStatus::RegistratonDenied => "registration denied",
+        NetworkRegistrationStatus::NotRegistered => "not registered",
+        NetworkRegistrationStatus::Unknown(_) => "unknown",
+    };
+
+    println!("Network: {}", registered);
+
+    if let Some(Response::OperatorSelection {
+        operator: Some(operator),
+        ..
+    }) = sim800.send_one(r#"AT+COPS?"#)?
+    {
+        println!("Operator: {}", operator);
+    }
+
+    if let Some(Response::SignalQuality { rssi, .. }) = sim800.send_one(r#"AT+CSQ"#)? {
+        match rssi {
+            SignalQualityRssi::Value(value) => println!("Signal: {} dBm", value),
+            SignalQualityRssi::Unknown => println!("Signal: unknown"),
+        }
+    }
+
+    match registration {
+        NetworkRegistrationStatus::Registered | NetworkRegistrationStatus::RegisteredRoaming => {
+            Ok(ExitCode::SUCCESS)
+        }
+        _ => Ok(ExitCode::from(EXIT_NOT_REGISTERED)),
+    }
+}
+
+pub fn info(sim800: &mut Sim800) -> Result<ExitCode, Box<dyn Error>> {
+    if let Some(Response::PhoneActivityStatus { status }) = sim800.send_one(r#"AT+CPAS"#)? {
+        let status = match status {
+            PhoneActivityStatus::Ready => "ready",
+            PhoneActivityStatus::Ringing => "ringing",
+            PhoneActivityStatus::CallInProgress => "call in progress",
+            PhoneActivityStatus::Unavailable => "unavailable",
+            PhoneActivityStatus::Unknown(_) => "unknown",
+        };
+
+        println!("Activity: {}", status);
+    }
+
+    if let Some(Response::BatteryCharge {
+        status,
+        level,
+        voltage,
+    }) = sim800.send_one(r#"AT+CBC"#)?
+    {
+        let status = match status {
+            BatteryChargeStatus::NotCharging => "not charging",
+            BatteryChargeStatus::Charging => "charging",
+            BatteryChargeStatus::Finished => "charging finished",
+        };
+
+        println!("Battery: {}%, {} mV, {}", level, voltage, status);
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+pub fn raw(sim800: &mut Sim800, commands: &[String]) -> Result<ExitCode, Box<dyn Error>> {
+    for command in commands {
+        println!(">> {}", command);
+
+        match sim800.send_list(command) {
+            Ok(responses) => {
+                for response in responses {
+                    println!("<< {:?}", response);
+                }
+
+                println!("<< OK");
+            }
+            Err(Sim800Error::Modem(error)) => {
+                println!("<< {}", error);
+
+                return Ok(ExitCode::from(EXIT_MODEM_ERROR));
+            }
+            Err(error) => return Err(error.into()),
+        }
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Describes call result code ending call, `None` for other responses.
+fn call_end_reason(response: &Response) -> Option<&'static str> {
+    match response {
+        Response::NoCarrier => Some("call ended"),
+        Response::Busy => Some("line busy"),
+        Response::NoAnswer => Some("no answer"),
+        Response::NoDialtone => Some("no dial tone"),
+        _ => None,
+    }
+}
+
+/// Calls number and waits until call ends or duration expires, then hangs up.
+pub fn call(
+    sim800: &mut Sim800,
+    number: &str,
+    duration: Duration,
+) -> Result<ExitCode, Box<dyn Error>> {
+    let unsolicited = sim800.subscribe();
+    let deadline = Instant::now() + duration;
+    // Call failing before `OK` ends dial command with call result code.
+    let failure = sim800
+        .send_list(&format!("ATD{};", number))?
+        .iter()
+        .find_map(call_end_reason);
+
+    println!("Calling {}.", number);
+
+    let result = match failure {
+        Some(reason) => Some(reason),
+        None => loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+
+            match unsolicited.recv_timeout(remaining) {
+                Ok(response) => match call_end_reason(&response) {
+                    Some(reason) => break Some(reason),
+                    None => continue,
+                },
+                Err(RecvTimeoutError::Timeout) => break None,
+                Err(RecvTimeoutError::Disconnected) => break Some("module disconnected"),
+            }
+        },
+    };
+
+    sim800.send(r#"ATH"#)?;
+
+    match result {
+        Some(reason) => {
+            println!("Call finished: {}.", reason);
+
+            Ok(ExitCode::from(EXIT_NOT_COMPLETED))
+        }
+        None => {
+            println!("Call finished.");
+
+            Ok(ExitCode::SUCCESS)
+        }
+    }
+}
+
+/// Sends USSD request and waits for `+CUSD` reply until timeout.
+pub fn ussd(sim800: &mut Sim800, request: &str) -> Result<ExitCode, Box<dyn Error>> {
+    let unsolicited = sim800.subscribe();
+    let charset = sim800.character_set();
+    let deadline = Instant::now() + sim800.command_timeout(r#"AT+CUSD"#);
+
+    sim800.send(&format!(r#"AT+CUSD=1,"{}",15"#, charset.encode(request)?))?;
+
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+
+        match unsolicited.recv_timeout(remaining) {
+            Ok(Response::UnstructuredData { status, text, .. }) => {
+                println!("{}", text.unwrap_or_default());
+
+                return match status {
+                    0 | 1 => Ok(ExitCode::SUCCESS),
+                    _ => Ok(ExitCode::from(EXIT_NOT_COMPLETED)),
+                };
+            }
+            Ok(_) => continue,
+            Err(RecvTimeoutError::Timeout) => return Ok(ExitCode::from(EXIT_TIMEOUT)),
+            Err(RecvTimeoutError::Disconnected) => return Ok(ExitCode::FAILURE),
+        }
+    }
+}
+
+/// Selects message format and character set used by SMS commands.
+pub fn prepare_sms(
+    sim800: &mut Sim800,
+    pdu_mode: bool,
+    character_set: CharacterSet,
+) -> Result<(), Box<dyn Error>> {
+    sim800.set_pdu_mode(pdu_mode)?;
+
+    if !pdu_mode {
+        sim800.send(r#"AT+CSDH=1"#)?;
+    }
+
+    sim800.set_character_set(character_set)?;
+
+    Ok(())
+}
+
+/// Stores complete messages in archive if selected.
+fn store(
+    archive: Option<&Archive>,
+    output: &Output,
+    messages: &[SmsMessage],
+) -> Result<(), Box<dyn Error>> {
+    if let Some(archive) = archive {
+        let count = archive.store_all(messages)?;
+
+        notice(output, &format!("Archived {} new message(s).", count));
+    }
+
+    Ok(())
+}
+
+pub fn sms_list(
+    sim800: &mut Sim800,
+    output: &Output,
+    archive: Option<&Archive>,
+) -> Result<ExitCode, Box<dyn Error>> {
+    let storage = watch::preferred_storage(sim800);
+    let messages = reassemble(sim800.send_list(list_command(sim800.pdu_mode()))?);
+
+    print!("{}", output.format_list(&messages, storage.as_ref())?);
+    store(archive, output, &messages)?;
+
+    Ok(ExitCode::SUCCESS)
+}
+
+pub fn sms_read(
+    sim800: &mut Sim800,
+    output: &Output,
+    archive: Option<&Archive>,
+    index: u16,
+) -> Result<ExitCode, Box<dyn Error>> {
+    let storage = watch::preferred_storage(sim800);
+    let messages = reassemble(watch::read_message(sim800, index)?);
+
+    if messages.is_empty() {
+        eprintln!("No message with index {}.", index);
+
+        return Ok(ExitCode::FAILURE);
+    }
+
+    print!("{}", output.format_list(&messages, storage.as_ref())?);
+    store(archive, output, &messages)?;
+
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Sends message in selected message format, optionally waits for delivery status reports. PDU
+/// mode splits long text into concatenated parts, text mode sends single part only.
+pub fn sms_send(
+    sim800: &mut Sim800,
+    output: &Output,
+    address: &str,
+    text: &str,
+    status_report: bool,
+    timeout: Duration,
+) -> Result<ExitCode, Box<dyn Error>> {
+    let segments = Sim800::segment_count(text)?;
+
+    if !is_registered(sim800)? {
+        notice(output, "Not registered in network.");
+
+        return Ok(ExitCode::from(EXIT_NOT_REGISTERED));
+    }
+
+    if status_report {
+        sim800.send(r#"AT+CNMI=2,1,0,1,0"#)?;
+        sim800.set_status_report(true)?;
+    }
+
+    let references = if sim800.pdu_mode() {
+        sim800.send_text(address, text)?
+    } else if segments > 1 {
+        notice(
+            output,
+            &format!(
+                "SMS message not sent, text requires {} parts, use PDU mode for long text.",
+                segments
+            ),
+        );
+
+        return Ok(ExitCode::from(EXIT_NOT_COMPLETED));
+    } else {
+        let reference = sim800
+            .send_sms(address, text)?
+            .ok_or_else(|| Sim800Error::MissingReply("AT+CMGS".into()))?;
+
+        vec![reference]
+    };
+
+    notice(
+        output,
+        &format!(
+            "SMS message sent in {} part(s), references {:?}.",
+            segments, references
+        ),
+    );
+
+    if !status_report {
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    let mut delivered = true;
+
+    sim800.wait_delivery(timeout)?;
+
+    for reference in references {
+        let message = match sim800.delivery_state(reference) {
+            Some(DeliveryState::Delivered) => format!("Message {} delivered.", reference),
+            Some(DeliveryState::Failed(status)) => {
+                delivered = false;
+
+                format!("Message {} not delivered, status {}.", reference, status)
+            }
+            _ => {
+                delivered = false;
+
+                format!("Message {} delivery pending.", reference)
+            }
+        };
+
+        notice(output, &message);
+    }
+
+    if delivered {
+        Ok(ExitCode::SUCCESS)
+    } else {
+        Ok(ExitCode::from(EXIT_NOT_COMPLETED))
+    }
+}
+
+/// Removes every selected message part with `AT+CMGD`, returns indices of parts not removed.
+fn remove_parts(
+    sim800: &mut Sim800,
+    output: &Output,
+    plan: &Plan,
+) -> Result<Vec<u16>, Box<dyn Error>> {
+    let mut result = Vec::new();
+
+    for index in plan.selected.iter().flat_map(|message| &message.indices) {
+        match sim800.send(&format!(r#"AT+CMGD={},0"#, index)) {
+            Ok(()) => {}
+            Err(Sim800Error::Modem(error)) => {
+                notice(
+                    output,
+                    &format!("Message part {} not removed: {}.", index, error),
+                );
+
+                result.push(*index);
+            }
+            Err(error) => return Err(error.into()),
+        }
+    }
+
+    Ok(result)
+}
+
+/// Removes messages with bulk command, returns indices of selected parts still present.
+fn remove_bulk(
+    sim800: &mut Sim800,
+    plan: &Plan,
+    mode: BulkDelete,
+) -> Result<Vec<u16>, Box<dyn Error>> {
+    let pdu_mode = sim800.pdu_mode();
+
+    sim800.send(&delete::bulk_command(mode, pdu_mode))?;
+
+    let remaining: Vec<u16> = sim800
+        .send_list(list_keep_status_command(pdu_mode))?
+        .iter()
+        .filter_map(|response| match response {
+            Response::ListSmsMessage { index, .. } => Some(*index),
+            _ => None,
+        })
+        .collect();
+
+    Ok(plan
+        .selected
+        .iter()
+        .flat_map(|message| message.indices.iter().copied())
+        .filter(|index| remaining.contains(index))
+        .collect())
+}
+
+/// Removes messages chosen by `selection` one by one or with bulk command if `bulk` mode given.
+/// Messages missing in archive kept unless `force` set, bulk command
+/// not sent at all if any selected message kept. Only prints messages to remove if `dry_run` set.
+pub fn sms_delete(
+    sim800: &mut Sim800,
+    output: &Output,
+    archive: Option<&Archive>,
+    selection: &Selection,
+    bulk: Option<BulkDelete>,
+    dry_run: bool,
+    force: bool,
+) -> Result<ExitCode, Box<dyn Error>> {
+    let storage = watch::preferred_storage(sim800);
+    let mut plan = selection.plan(
+        reassemble(sim800.send_list(list_keep_status_command(sim800.pdu_mode()))?),
+        OffsetDateTime::now_utc(),
+    );
+
+    match (force, archive) {
+        (true, _) => {}
+        (false, Some(archive)) => plan.refuse(|message| archive.contains(message))?,
+        (false, None) => plan.refuse_all(),
+    }
+
+    if bulk.is_some() && !plan.refused.is_empty() {
+        plan.refuse_all();
+    }
+
+    let failed = if dry_run {
+        print!("{}", output.format_list(&plan.selected, storage.as_ref())?);
+
+        notice(
+            output,
+            &format!(
+                "Would remove {} message(s) in {} part(s).",
+                plan.selected.len(),
+                plan.part_count()
+            ),
+        );
+
+        Vec::new()
+    } else {
+        let failed = match bulk {
+            _ if plan.selected.is_empty() => Vec::new(),
+            Some(mode) => remove_bulk(sim800, &plan, mode)?,
+            None => remove_parts(sim800, output, &plan)?,
+        };
+        let removed = plan
+            .selected
+            .iter()
+            .filter(|message| !message.indices.iter().any(|index| failed.contains(index)))
+            .count();
+
+        notice(
+            output,
+            &format!(
+                "Removed {} of {} message(s), {} part(s).",
+                removed,
+                plan.selected.len(),
+                plan.part_count() - failed.len()
+            ),
+        );
+
+        if !failed.is_empty() {
+            notice(output, &format!("Message parts {:?} not removed.", failed));
+        }
+
+        failed
+    };
+
+    for message in &plan.kept {
+        notice(
+            output,
+            &format!(
+                "Message parts {:?} kept until all parts received.",
+                message.indices
+            ),
+        );
+    }
+
+    for message in &plan.refused {
+        notice(
+            output,
+            &format!(
+                "Message parts {:?} not archived, use --archive to store them or --force to remove.",
+                message.indices
+            ),
+        );
+    }
+
+    if !plan.missing.is_empty() {
+        notice(
+            output,
+            &format!("Message parts {:?} not found.", plan.missing),
+        );
+    }
+
+    if failed.is_empty() && plan.refused.is_empty() && plan.missing.is_empty() {
+        Ok(ExitCode::SUCCESS)
+    } else {
+        Ok(ExitCode::from(EXIT_NOT_COMPLETED))
+    }
+}
+
+/// Shows received messages until interrupted, messages stored in archive before removal.
+pub fn sms_watch(
+    sim800: &mut Sim800,
+    output: &Output,
+    archive: Option<&Archive>,
+    delete: bool,
+    path: Option<&PathBuf>,
+) -> Result<ExitCode, Box<dyn Error>> {
+    let running = Arc::new(AtomicBool::new(true));
+    let handler = running.clone();
+    let mut sink: Box<dyn Write> = match path {
+        Some(path) => Box::new(OpenOptions::new().create(true).append(true).open(path)?),
+        None => Box::new(stdout()),
+    };
+
+    ctrlc::set_handler(move || handler.store(false, Ordering::Relaxed))?;
+
+    notice(output, "Waiting for SMS messages, press Ctrl-C to stop.");
+
+    if let Some(header) = output.header() {
+        writeln!(sink, "{}", header)?;
+    }
+
+    watch::watch(sim800, &running, delete, |message, storage| {
+        if let Some(archive) = archive {
+            archive.store(message)?;
+        }
+
+        writeln!(sink, "{}", output.format_message(message, Some(storage))?)?;
+        sink.flush()?;
+
+        Ok(())
+    })?;
+
+    Ok(ExitCode::SUCCESS)
+}
+
+pub fn archive_search(
+    archive: &Archive,
+    output: &Output,
+    query: &Query,
+) -> Result<ExitCode, Box<dyn Error>> {
+    let messages = archive.search(query)?;
+
+    print!("{}", output.format_list(&messages, None)?);
+
+    Ok(ExitCode::SUCCESS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arguments::OutputFormat;
+    use crate::arguments::TimeZone;
+    use sim800_reader::address::Address;
+    use sim800_reader::parser::SmsMessageStatus;
+    use sim800_reader::pdu::encode_hex;
+    use sim800_reader::simulator::Simulator;
+    use sim800_reader::simulator::SimulatorHandle;
+    use sim800_reader::transport::PipeTransport;
+    use sim800_reader::transport::ReplayTransport;
+    use std::io::Cursor;
+    use time::macros::datetime;
+    use time::UtcOffset;
+
+    const READ_TIMEOUT: Duration = Duration::from_millis(50);
+
+    fn connect(simulator: Simulator) -> (Sim800, SimulatorHandle) {
+        let (host, module) = PipeTransport::pair(READ_TIMEOUT);
+        let handle = simulator.spawn(Box::new(module)).unwrap();
+        let sim800 = Sim800::new(Box::new(host)).unwrap();
+
+        (sim800, handle)
+    }
+
+    fn disconnect(sim800: Sim800, handle: SimulatorHandle) {
+        sim800.join().unwrap();
+        handle.join().unwrap();
+    }
+
+    /// Calls with given simulator state, returns exit code and call time.
+    fn call_with(simulator: Simulator, duration: Duration) -> (ExitCode, Duration) {
+        let (mut sim800, handle) = connect(simulator);
+        let start = Instant::now();
+        let code = call(&mut sim800, "+123", duration).unwrap();
+        let elapsed = start.elapsed();
+
+        assert!(matches!(
+            sim800.send_one("AT+CPAS").unwrap(),
+            Some(Response::PhoneActivityStatus {
+                status: PhoneActivityStatus::Ready
+            })
+        ));
+
+        disconnect(sim800, handle);
+
+        (code, elapsed)
+    }
+
+    #[test]
+    fn call_fails_without_network() {
+        let mut simulator = Simulator::new();
+
+        simulator.set_registered(false);
+
+        let (code, elapsed) = call_with(simulator, Duration::from_secs(20));
+
+        assert_eq!(code, ExitCode::from(EXIT_NOT_COMPLETED));
+        assert!(elapsed < Duration::from_secs(5), "{:?}", elapsed);
+    }
+
+    #[test]
+    fn call_fails_with_busy_line() {
+        let mut simulator = Simulator::new();
+
+        simulator.set_busy(true);
+
+        let (code, elapsed) = call_with(simulator, Duration::from_secs(20));
+
+        assert_eq!(code, ExitCode::from(EXIT_NOT_COMPLETED));
+        assert!(elapsed < Duration::from_secs(5), "{:?}", elapsed);
+    }
+
+    #[test]
+    fn call_hangs_up_after_duration() {
+        let duration = Duration::from_millis(500);
+        let (code, elapsed) = call_with(Simulator::new(), duration);
+
+        assert_eq!(code, ExitCode::SUCCESS);
+        assert!(elapsed >= duration, "{:?}", elapsed);
+    }
+
+    #[test]
+    fn call_ends_when_remote_hangs_up() {
+        let (mut sim800, handle) = connect(Simulator::new());
+        let start = Instant::now();
+        let code = std::thread::scope(|scope| {
+            scope.spawn(|| {
+                std::thread::sleep(Duration::from_millis(300));
+                handle.emit("NO CARRIER").unwrap();
+            });
+
+            call(&mut sim800, "+123", Duration::from_secs(20)).unwrap()
+        });
+
+        assert_eq!(code, ExitCode::from(EXIT_NOT_COMPLETED));
+        assert!(start.elapsed() < Duration::from_secs(5));
+
+        disconnect(sim800, handle);
+    }
+
+    /// Sends text in given message format, returns exit code and texts received by simulator.
+    fn send_with(pdu_mode: bool, text: &str) -> (ExitCode, Vec<String>) {
+        let (mut sim800, handle) = connect(Simulator::new());
+        let output = Output::new(OutputFormat::Text, TimeZone::Original, UtcOffset::UTC);
+
+        prepare_sms(&mut sim800, pdu_mode, CharacterSet::Gsm).unwrap();
+
+        let code = sms_send(&mut sim800, &output, "+123", text, false, READ_TIMEOUT).unwrap();
+        let sent = handle
+            .simulator()
+            .sent()
+            .iter()
+            .map(|message| message.text.clone())
+            .collect();
+
+        disconnect(sim800, handle);
+
+        (code, sent)
+    }
+
+    #[test]
+    fn sms_send_uses_text_mode() {
+        assert_eq!(
+            send_with(false, "hello"),
+            (ExitCode::SUCCESS, vec!["hello".into()])
+        );
+    }
+
+    #[test]
+    fn sms_send_splits_text_in_pdu_mode() {
+        let (code, sent) = send_with(true, &"x".repeat(200));
+
+        assert_eq!(code, ExitCode::SUCCESS);
+        assert_eq!(sent, ["x".repeat(153), "x".repeat(47)]);
+    }
+
+    #[test]
+    fn sms_send_refuses_long_text_in_text_mode() {
+        assert_eq!(
+            send_with(false, &"x".repeat(200)),
+            (ExitCode::from(EXIT_NOT_COMPLETED), vec![])
+        );
+    }
+
+    /// Removes read messages from "+123" and "+456" with given options, messages from
+    /// `archived` senders stored in archive first. Returns exit code and remaining indices.
+    fn delete_with(
+        archived: Option<&[&str]>,
+        bulk: Option<BulkDelete>,
+        force: bool,
+    ) -> (ExitCode, Vec<u16>) {
+        let timestamp = datetime!(2023-12-31 23:30:00 +2);
+        let mut simulator = Simulator::new();
+
+        for address in ["+123", "+456"] {
+            simulator.add_message(SmsMessageStatus::ReceivedRead, address, timestamp, "hello");
+        }
+
+        let archive = archived.map(|addresses| {
+            let archive = Archive::open(":memory:").unwrap();
+
+            for address in addresses {
+                archive
+                    .store(&SmsMessage {
+                        indices: Vec::new(),
+                        status: SmsMessageStatus::ReceivedRead,
+                        address: Address::parse(address),
+                        address_text: None,
+                        timestamp: Some(timestamp),
+                        text: "hello".into(),
+                        total: 1,
+                        missing: Vec::new(),
+                    })
+                    .unwrap();
+            }
+
+            archive
+        });
+        let (mut sim800, handle) = connect(simulator);
+        let output = Output::new(OutputFormat::Text, TimeZone::Original, UtcOffset::UTC);
+        let selection = match bulk {
+            Some(mode) => Selection::bulk(mode),
+            None => Selection::new(&[], &[], None, None),
+        };
+
+        prepare_sms(&mut sim800, true, CharacterSet::Gsm).unwrap();
+
+        let code = sms_delete(
+            &mut sim800,
+            &output,
+            archive.as_ref(),
+            &selection,
+            bulk,
+            false,
+            force,
+        )
+        .unwrap();
+        let remaining = handle.simulator().messages().keys().copied().collect();
+
+        disconnect(sim800, handle);
+
+        (code, remaining)
+    }
+
+    #[test]
+    fn sms_delete_refuses_without_archive() {
+        assert_eq!(
+            delete_with(None, None, false),
+            (ExitCode::from(EXIT_NOT_COMPLETED), vec![1, 2])
+        );
+    }
+
+    #[test]
+    fn sms_delete_refuses_messages_missing_in_archive() {
+        assert_eq!(
+            delete_with(Some(&["+123"]), None, false),
+            (ExitCode::from(EXIT_NOT_COMPLETED), vec![2])
+        );
+        assert_eq!(
+            delete_with(Some(&["+123", "+456"]), None, false),
+            (ExitCode::SUCCESS, vec![])
+        );
+    }
+
+    #[test]
+    fn sms_delete_skips_bulk_command_if_any_message_refused() {
+        assert_eq!(
+            delete_with(Some(&["+123"]), Some(BulkDelete::Read), false),
+            (ExitCode::from(EXIT_NOT_COMPLETED), vec![1, 2])
+        );
+    }
+
+    #[test]
+    fn sms_delete_removes_unarchived_messages_with_force() {
+        assert_eq!(delete_with(None, None, true), (ExitCode::SUCCESS, vec![]));
+        assert_eq!(
+            delete_with(Some(&[]), Some(BulkDelete::Read), true),
+            (ExitCode::SUCCESS, vec![])
+        );
+    }
+
+    #[test]
+    fn status_fails_without_registration_reply() {
+        let transcript = format!(
+            "0 > {}\n1 < {}\n",
+            encode_hex(b"AT+CREG?\r\n"),
+            encode_hex(b"\r\nOK\r\n")
+        );
+        let replay = ReplayTransport::new(Cursor::new(transcript), READ_TIMEOUT).unwrap();
+        let mut sim800 = Sim800::new(Box::new(replay)).unwrap();
+        let error = status(&mut sim800).unwrap_err();
+
+        assert!(matches!(
+            error.downcast_ref::<Sim800Error>(),
+            Some(Sim800Error::MissingReply(command)) if command == "AT+CREG?"
+        ));
+
+        sim800.join().unwrap();
+    }
+}
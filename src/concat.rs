@@ -0,0 +1,475 @@
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+
+use log::warn;
+use time::OffsetDateTime;
+
+use crate::address::Address;
+use crate::parser::Response;
+use crate::parser::SmsMessageStatus;
+use crate::pdu::text_length;
+use crate::pdu::Alphabet;
+use crate::pdu::Concatenation;
+use crate::pdu::DataCoding;
+use crate::pdu::InformationElement;
+use crate::pdu::PduError;
+use crate::pdu::SmsSubmit;
+use crate::pdu::UserData;
+use crate::pdu::UserDataHeader;
+
+/// Logical SMS message assembled from one or more stored message parts.
+#[derive(Debug, Clone)]
+pub struct SmsMessage {
+    pub indices: Vec<u16>,
+    pub status: SmsMessageStatus,
+    pub address: Address,
+    pub address_text: Option<String>,
+    pub timestamp: Option<OffsetDateTime>,
+    pub text: String,
+    pub total: u8,
+    pub missing: Vec<u8>,
+}
+
+impl SmsMessage {
+    pub fn is_complete(&self) -> bool {
+        self.missing.is_empty()
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+struct PartKey {
+    address: Address,
+    reference: u16,
+    total: u8,
+}
+
+#[derive(Debug)]
+struct Part {
+    index: u16,
+    status: SmsMessageStatus,
+    address_text: Option<String>,
+    timestamp: Option<OffsetDateTime>,
+    text: String,
+}
+
+/// Parts of one concatenated message by sequence number. Duplicate parts and parts with sequence
+/// number out of range are not joined, their storage indices kept for deletion.
+#[derive(Debug, Default)]
+struct Group {
+    parts: BTreeMap<u8, Part>,
+    extra: Vec<u16>,
+}
+
+#[derive(Debug)]
+enum Entry {
+    Single(SmsMessage),
+    Parts(PartKey),
+}
+
+/// Groups `ListSmsMessage` responses into logical messages. Parts are matched by sender address,
+/// concatenation reference and part count, ordered by sequence number. Messages keep the order
+/// of the first received part, other responses are ignored. Duplicate parts, like retransmitted
+/// ones, and parts with invalid sequence number are reported and left out of text.
+pub fn reassemble<I>(responses: I) -> Vec<SmsMessage>
+where
+    I: IntoIterator<Item = Response>,
+{
+    let mut entries = Vec::new();
+    let mut groups: HashMap<PartKey, Group> = HashMap::new();
+
+    for response in responses {
+        if let Response::ListSmsMessage {
+            index,
+            status,
+            address,
+            address_text,
+            timestamp,
+            text,
+            pdu,
+        } = response
+        {
+            match pdu.and_then(|pdu| pdu.concatenation()) {
+                Some(concatenation) if concatenation.total > 1 => {
+                    let key = PartKey {
+                        address,
+                        reference: concatenation.reference,
+                        total: concatenation.total,
+                    };
+                    let group = groups.entry(key.clone()).or_insert_with(|| {
+                        entries.push(Entry::Parts(key));
+
+                        Group::default()
+                    });
+                    let sequence = concatenation.sequence;
+
+                    if sequence == 0 || sequence > concatenation.total {
+                        warn!(
+                            "Message part {} has sequence number {} of {} parts",
+                            index, sequence, concatenation.total
+                        );
+
+                        group.extra.push(index);
+                    } else if let Some(part) = group.parts.get(&sequence) {
+                        warn!(
+                            "Message part {} duplicates part {} with sequence number {}",
+                            index, part.index, sequence
+                        );
+
+                        group.extra.push(index);
+                    } else {
+                        group.parts.insert(
+                            sequence,
+                            Part {
+                                index,
+                                status,
+                                address_text,
+                                timestamp,
+                                text,
+                            },
+                        );
+                    }
+                }
+                _ => entries.push(Entry::Single(SmsMessage {
+                    indices: vec![index],
+                    status,
+                    address,
+                    address_text,
+                    timestamp,
+                    text,
+                    total: 1,
+                    missing: Vec::new(),
+                })),
+            }
+        }
+    }
+
+    entries
+        .into_iter()
+        .filter_map(|entry| match entry {
+            Entry::Single(message) => Some(message),
+            Entry::Parts(key) => groups
+                .remove(&key)
+                .map(|group| assemble(key.address, key.total, group)),
+        })
+        .collect()
+}
+
+fn assemble(address: Address, total: u8, group: Group) -> SmsMessage {
+    let parts = group.parts;
+    let first = parts.values().next();
+    let unread = parts
+        .values()
+        .any(|part| part.status == SmsMessageStatus::ReceivedUnread);
+    let status = match first {
+        Some(part) if !unread => part.status.clone(),
+        _ => SmsMessageStatus::ReceivedUnread,
+    };
+
+    SmsMessage {
+        indices: parts
+            .values()
+            .map(|part| part.index)
+            .chain(group.extra)
+            .collect(),
+        status,
+        address,
+        address_text: first.and_then(|part| part.address_text.clone()),
+        timestamp: first.and_then(|part| part.timestamp),
+        text: parts.values().map(|part| part.text.as_str()).collect(),
+        total,
+        missing: (1..=total)
+            .filter(|sequence| !parts.contains_key(sequence))
+            .collect(),
+    }
+}
+
+/// Returns maximum text length of single and concatenated message part in alphabet units.
+fn segment_length(alphabet: Alphabet) -> (usize, usize) {
+    match alphabet {
+        Alphabet::Gsm7 => (160, 153),
+        Alphabet::Ucs2 => (70, 67),
+        Alphabet::Data8 => (140, 134),
+    }
+}
+
+/// Splits text into message parts, characters are never split between parts.
+pub fn split_text(text: &str, alphabet: Alphabet) -> Vec<String> {
+    let (single, part) = segment_length(alphabet);
+
+    if text_length(text, alphabet) <= single {
+        return vec![text.into()];
+    }
+
+    let mut result = Vec::new();
+    let mut current = String::new();
+    let mut length = 0;
+
+    for ch in text.chars() {
+        let width = text_length(ch.encode_utf8(&mut [0; 4]), alphabet);
+
+        if length + width > part {
+            result.push(current.split_off(0));
+            length = 0;
+        }
+
+        current.push(ch);
+        length += width;
+    }
+
+    result.push(current);
+    result
+}
+
+/// Returns number of messages required to send text, alphabet is selected automatically. Fails
+/// when text does not fit into 255 parts.
+pub fn segment_count(text: &str) -> Result<usize, PduError> {
+    let count = split_text(text, DataCoding::for_text(text).alphabet).len();
+
+    part_total(count)?;
+
+    Ok(count)
+}
+
+/// Returns concatenation header part count, header can not address more than 255 parts.
+fn part_total(count: usize) -> Result<u8, PduError> {
+    u8::try_from(count).map_err(|_| PduError::TooManyParts(count))
+}
+
+/// Creates SMS-SUBMIT messages for text. Messages longer than single part are split and every
+/// part gets concatenation header with given reference number. Fails when text does not fit
+/// into 255 parts.
+pub fn split_submit(
+    destination: &Address,
+    text: &str,
+    reference: u8,
+) -> Result<Vec<SmsSubmit>, PduError> {
+    let data_coding = DataCoding::for_text(text);
+    let parts = split_text(text, data_coding.alphabet);
+    let total = part_total(parts.len())?;
+
+    Ok(parts
+        .iter()
+        .zip(1..=total)
+        .map(|(part, sequence)| {
+            let mut user_data = UserData::text(part);
+
+            if total > 1 {
+                user_data.header = Some(UserDataHeader {
+                    elements: vec![InformationElement::Concatenation8(Concatenation {
+                        reference: reference as u16,
+                        total,
+                        sequence,
+                    })],
+                });
+            }
+
+            SmsSubmit::new(destination.clone(), data_coding, user_data)
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdu::Pdu;
+
+    fn single(index: u16, address: &str, text: &str) -> Response {
+        Response::ListSmsMessage {
+            index,
+            status: SmsMessageStatus::ReceivedRead,
+            address: Address::parse(address),
+            address_text: None,
+            timestamp: None,
+            text: text.into(),
+            pdu: None,
+        }
+    }
+
+    fn part(
+        index: u16,
+        address: &str,
+        reference: u16,
+        total: u8,
+        sequence: u8,
+        text: &str,
+    ) -> Response {
+        let mut user_data = UserData::text(text);
+
+        user_data.header = Some(UserDataHeader {
+            elements: vec![InformationElement::Concatenation8(Concatenation {
+                reference,
+                total,
+                sequence,
+            })],
+        });
+
+        let submit = SmsSubmit::new(
+            Address::parse(address),
+            DataCoding::new(Alphabet::Gsm7),
+            user_data,
+        );
+
+        match single(index, address, text) {
+            Response::ListSmsMessage {
+                index,
+                status,
+                address,
+                address_text,
+                timestamp,
+                text,
+                ..
+            } => Response::ListSmsMessage {
+                index,
+                status,
+                address,
+                address_text,
+                timestamp,
+                text,
+                pdu: Some(Box::new(Pdu::Submit(submit))),
+            },
+            _ => unreachable!(),
+        }
+    }
+
+    /// Returns indices, text and missing parts of messages.
+    fn summary(messages: &[SmsMessage]) -> Vec<(Vec<u16>, &str, Vec<u8>)> {
+        messages
+            .iter()
+            .map(|message| {
+                (
+                    message.indices.clone(),
+                    message.text.as_str(),
+                    message.missing.clone(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn joins_parts_out_of_order() {
+        let messages = reassemble([
+            part(3, "+123", 7, 3, 3, "c"),
+            single(1, "+123", "single"),
+            part(2, "+123", 7, 3, 1, "a"),
+            part(4, "+123", 7, 3, 2, "b"),
+        ]);
+
+        assert_eq!(
+            summary(&messages),
+            [(vec![2, 4, 3], "abc", vec![]), (vec![1], "single", vec![])]
+        );
+        assert!(messages.iter().all(SmsMessage::is_complete));
+    }
+
+    #[test]
+    fn reports_missing_parts() {
+        let messages = reassemble([part(1, "+123", 7, 3, 2, "b")]);
+
+        assert_eq!(summary(&messages), [(vec![1], "b", vec![1, 3])]);
+        assert!(!messages[0].is_complete());
+    }
+
+    #[test]
+    fn keeps_indices_of_duplicate_parts() {
+        let messages = reassemble([
+            part(1, "+123", 7, 2, 1, "a"),
+            part(2, "+123", 7, 2, 1, "a"),
+            part(3, "+123", 7, 2, 2, "b"),
+        ]);
+
+        assert_eq!(summary(&messages), [(vec![1, 3, 2], "ab", vec![])]);
+    }
+
+    #[test]
+    fn leaves_out_of_range_parts_out_of_text() {
+        let messages = reassemble([
+            part(1, "+123", 7, 2, 0, "x"),
+            part(2, "+123", 7, 2, 1, "a"),
+            part(3, "+123", 7, 2, 3, "y"),
+            part(4, "+123", 7, 2, 2, "b"),
+        ]);
+
+        assert_eq!(summary(&messages), [(vec![2, 4, 1, 3], "ab", vec![])]);
+    }
+
+    #[test]
+    fn separates_interleaved_groups() {
+        let messages = reassemble([
+            part(1, "+123", 7, 2, 1, "a"),
+            part(2, "+456", 7, 2, 1, "x"),
+            part(3, "+123", 8, 2, 1, "c"),
+            part(4, "+123", 7, 2, 2, "b"),
+            part(5, "+456", 7, 2, 2, "y"),
+            part(6, "+123", 7, 3, 1, "e"),
+        ]);
+
+        assert_eq!(
+            summary(&messages),
+            [
+                (vec![1, 4], "ab", vec![]),
+                (vec![2, 5], "xy", vec![]),
+                (vec![3], "c", vec![2]),
+                (vec![6], "e", vec![2, 3]),
+            ]
+        );
+    }
+
+    /// Returns character counts of split text parts.
+    fn lengths(text: &str, alphabet: Alphabet) -> Vec<usize> {
+        split_text(text, alphabet)
+            .iter()
+            .map(|part| part.chars().count())
+            .collect()
+    }
+
+    #[test]
+    fn splits_gsm7_text_at_part_boundaries() {
+        assert_eq!(lengths(&"a".repeat(160), Alphabet::Gsm7), [160]);
+        assert_eq!(lengths(&"a".repeat(161), Alphabet::Gsm7), [153, 8]);
+        assert_eq!(lengths(&"a".repeat(306), Alphabet::Gsm7), [153, 153]);
+        assert_eq!(lengths(&"a".repeat(307), Alphabet::Gsm7), [153, 153, 1]);
+    }
+
+    #[test]
+    fn splits_ucs2_text_at_part_boundaries() {
+        assert_eq!(lengths(&"ж".repeat(70), Alphabet::Ucs2), [70]);
+        assert_eq!(lengths(&"ж".repeat(71), Alphabet::Ucs2), [67, 4]);
+        assert_eq!(lengths(&"ж".repeat(134), Alphabet::Ucs2), [67, 67]);
+    }
+
+    #[test]
+    fn keeps_extension_characters_whole() {
+        // Extension characters take two septets.
+        assert_eq!(lengths(&"€".repeat(80), Alphabet::Gsm7), [80]);
+        assert_eq!(lengths(&"€".repeat(81), Alphabet::Gsm7), [76, 5]);
+
+        let text = format!("{}€{}", "a".repeat(151), "a".repeat(10));
+
+        assert_eq!(lengths(&text, Alphabet::Gsm7), [152, 10]);
+
+        let text = format!("{}€{}", "a".repeat(152), "a".repeat(10));
+        let parts = split_text(&text, Alphabet::Gsm7);
+
+        assert_eq!(parts[0], "a".repeat(152));
+        assert_eq!(parts[1], format!("€{}", "a".repeat(10)));
+    }
+
+    #[test]
+    fn rejects_text_over_255_parts() {
+        let address = Address::parse("+123");
+
+        assert_eq!(segment_count(&"a".repeat(153 * 255)), Ok(255));
+        assert_eq!(
+            segment_count(&"a".repeat(153 * 255 + 1)),
+            Err(PduError::TooManyParts(256))
+        );
+        assert_eq!(
+            split_submit(&address, &"a".repeat(153 * 255), 0).map(|parts| parts.len()),
+            Ok(255)
+        );
+        assert!(matches!(
+            split_submit(&address, &"a".repeat(153 * 256), 0),
+            Err(PduError::TooManyParts(256))
+        ));
+    }
+}
@@ -0,0 +1,387 @@
+use crate::arguments::BulkDelete;
+use crate::arguments::MessageStatus;
+use sim800_reader::address::Address;
+use sim800_reader::concat::SmsMessage;
+use sim800_reader::parser::SmsMessageStatus;
+use time::Duration;
+use time::OffsetDateTime;
+
+/// Statuses removed when nothing selected.
+const DEFAULT_STATUSES: [SmsMessageStatus; 2] =
+    [SmsMessageStatus::ReceivedRead, SmsMessageStatus::StoredSent];
+
+/// Messages to remove. Parts of incomplete messages kept unless selected by index, indices
+/// missing in storage and messages refused because they are not archived reported separately.
+#[derive(Debug, Default)]
+pub struct Plan {
+    pub selected: Vec<SmsMessage>,
+    pub kept: Vec<SmsMessage>,
+    pub refused: Vec<SmsMessage>,
+    pub missing: Vec<u16>,
+}
+
+impl Plan {
+    /// Moves selected messages not accepted by `archived` to refused messages.
+    pub fn refuse<F, E>(&mut self, mut archived: F) -> Result<(), E>
+    where
+        F: FnMut(&SmsMessage) -> Result<bool, E>,
+    {
+        let mut selected = Vec::new();
+
+        for message in self.selected.drain(..) {
+            if archived(&message)? {
+                selected.push(message);
+            } else {
+                self.refused.push(message);
+            }
+        }
+
+        self.selected = selected;
+
+        Ok(())
+    }
+
+    /// Moves all selected messages to refused messages.
+    pub fn refuse_all(&mut self) {
+        self.refused.append(&mut self.selected);
+    }
+
+    pub fn part_count(&self) -> usize {
+        self.selected
+            .iter()
+            .map(|message| message.indices.len())
+            .sum()
+    }
+}
+
+/// Conditions to select messages, message selected if all given conditions match.
+#[derive(Debug)]
+pub struct Selection {
+    indices: Vec<u16>,
+    statuses: Vec<SmsMessageStatus>,
+    from: Option<Address>,
+    older_than: Option<Duration>,
+    keep_incomplete: bool,
+}
+
+impl Selection {
+    pub fn new(
+        indices: &[u16],
+        statuses: &[MessageStatus],
+        from: Option<&str>,
+        older_than: Option<u32>,
+    ) -> Self {
+        let mut statuses: Vec<_> = statuses.iter().map(|&status| convert(status)).collect();
+
+        if indices.is_empty() && statuses.is_empty() && from.is_none() && older_than.is_none() {
+            statuses.extend(DEFAULT_STATUSES);
+        }
+
+        Self {
+            indices: indices.to_vec(),
+            statuses,
+            from: from.map(Address::parse),
+            older_than: older_than.map(|days| Duration::days(days.into())),
+            keep_incomplete: true,
+        }
+    }
+
+    /// Selects messages removed by bulk command, including parts of incomplete messages.
+    pub fn bulk(mode: BulkDelete) -> Self {
+        Self {
+            indices: Vec::new(),
+            statuses: bulk_statuses(mode).to_vec(),
+            from: None,
+            older_than: None,
+            keep_incomplete: false,
+        }
+    }
+
+    fn matches(&self, message: &SmsMessage, now: OffsetDateTime) -> bool {
+        let status = self.statuses.is_empty() || self.statuses.contains(&message.status);
+        let from = match &self.from {
+            Some(from) => from.number().eq_ignore_ascii_case(message.address.number()),
+            None => true,
+        };
+        let age = match (self.older_than, message.timestamp) {
+            (Some(age), Some(timestamp)) => timestamp < now - age,
+            (Some(_), None) => false,
+            (None, _) => true,
+        };
+
+        status && from && age
+    }
+
+    pub fn plan(&self, messages: Vec<SmsMessage>, now: OffsetDateTime) -> Plan {
+        let mut result = Plan::default();
+
+        for mut message in messages {
+            if !self.matches(&message, now) {
+                continue;
+            }
+
+            if !self.indices.is_empty() {
+                message.indices.retain(|index| self.indices.contains(index));
+
+                if !message.indices.is_empty() {
+                    result.selected.push(message);
+                }
+            } else if message.is_complete() || !self.keep_incomplete {
+                result.selected.push(message);
+            } else {
+                result.kept.push(message);
+            }
+        }
+
+        result.missing = self
+            .indices
+            .iter()
+            .filter(|index| {
+                !result
+                    .selected
+                    .iter()
+                    .any(|message| message.indices.contains(index))
+            })
+            .copied()
+            .collect();
+
+        result
+    }
+}
+
+fn convert(status: MessageStatus) -> SmsMessageStatus {
+    match status {
+        MessageStatus::Unread => SmsMessageStatus::ReceivedUnread,
+        MessageStatus::Read => SmsMessageStatus::ReceivedRead,
+        MessageStatus::Unsent => SmsMessageStatus::StoredUnsent,
+        MessageStatus::Sent => SmsMessageStatus::StoredSent,
+    }
+}
+
+fn bulk_statuses(mode: BulkDelete) -> &'static [SmsMessageStatus] {
+    match mode {
+        BulkDelete::Read => &[SmsMessageStatus::ReceivedRead],
+        BulkDelete::ReadSent => &[SmsMessageStatus::ReceivedRead, SmsMessageStatus::StoredSent],
+        BulkDelete::ReadSentUnsent => &[
+            SmsMessageStatus::ReceivedRead,
+            SmsMessageStatus::StoredSent,
+            SmsMessageStatus::StoredUnsent,
+        ],
+        BulkDelete::All => &[
+            SmsMessageStatus::ReceivedUnread,
+            SmsMessageStatus::ReceivedRead,
+            SmsMessageStatus::StoredSent,
+            SmsMessageStatus::StoredUnsent,
+        ],
+        BulkDelete::Unread => &[SmsMessageStatus::ReceivedUnread],
+        BulkDelete::Sent => &[SmsMessageStatus::StoredSent],
+        BulkDelete::Unsent => &[SmsMessageStatus::StoredUnsent],
+        BulkDelete::Inbox => &[
+            SmsMessageStatus::ReceivedUnread,
+            SmsMessageStatus::ReceivedRead,
+        ],
+    }
+}
+
+/// Returns command removing messages in bulk. Modes defined in 3GPP TS 27.005 use `AT+CMGD`
+/// delete flag, other modes use SIM800 specific `AT+CMGDA` with type depending on message format.
+pub fn bulk_command(mode: BulkDelete, pdu_mode: bool) -> String {
+    let (code, name) = match mode {
+        BulkDelete::Read => return "AT+CMGD=1,1".into(),
+        BulkDelete::ReadSent => return "AT+CMGD=1,2".into(),
+        BulkDelete::ReadSentUnsent => return "AT+CMGD=1,3".into(),
+        BulkDelete::All => return "AT+CMGD=1,4".into(),
+        BulkDelete::Unread => (2, "DEL UNREAD"),
+        BulkDelete::Sent => (3, "DEL SENT"),
+        BulkDelete::Unsent => (4, "DEL UNSENT"),
+        BulkDelete::Inbox => (5, "DEL INBOX"),
+    };
+
+    if pdu_mode {
+        format!("AT+CMGDA={}", code)
+    } else {
+        format!(r#"AT+CMGDA="{}""#, name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::datetime;
+
+    const NOW: OffsetDateTime = datetime!(2024-01-10 12:00:00 UTC);
+
+    fn message(indices: &[u16], status: SmsMessageStatus, address: &str) -> SmsMessage {
+        SmsMessage {
+            indices: indices.to_vec(),
+            status,
+            address: Address::parse(address),
+            address_text: None,
+            timestamp: Some(NOW - Duration::days(1)),
+            text: "hello".into(),
+            total: indices.len() as u8,
+            missing: Vec::new(),
+        }
+    }
+
+    fn messages() -> Vec<SmsMessage> {
+        vec![
+            message(&[1], SmsMessageStatus::ReceivedUnread, "+123"),
+            message(&[2], SmsMessageStatus::ReceivedRead, "+123"),
+            message(&[3, 4], SmsMessageStatus::ReceivedRead, "+456"),
+            message(&[5], SmsMessageStatus::StoredSent, "+456"),
+            message(&[6], SmsMessageStatus::StoredUnsent, "+789"),
+        ]
+    }
+
+    fn indices(messages: &[SmsMessage]) -> Vec<u16> {
+        messages
+            .iter()
+            .flat_map(|message| message.indices.iter().copied())
+            .collect()
+    }
+
+    fn selected(selection: &Selection, messages: Vec<SmsMessage>) -> Vec<u16> {
+        indices(&selection.plan(messages, NOW).selected)
+    }
+
+    #[test]
+    fn selects_read_and_sent_by_default() {
+        assert_eq!(
+            selected(&Selection::new(&[], &[], None, None), messages()),
+            [2, 3, 4, 5]
+        );
+    }
+
+    #[test]
+    fn selects_by_index() {
+        let plan = Selection::new(&[1, 4, 9], &[], None, None).plan(messages(), NOW);
+
+        assert_eq!(indices(&plan.selected), [1, 4]);
+        assert_eq!(plan.missing, [9]);
+        assert_eq!(plan.part_count(), 2);
+    }
+
+    #[test]
+    fn selects_by_status() {
+        for (statuses, expected) in [
+            (vec![MessageStatus::Unread], vec![1]),
+            (vec![MessageStatus::Read], vec![2, 3, 4]),
+            (vec![MessageStatus::Sent, MessageStatus::Unsent], vec![5, 6]),
+        ] {
+            assert_eq!(
+                selected(&Selection::new(&[], &statuses, None, None), messages()),
+                expected,
+                "{:?}",
+                statuses
+            );
+        }
+    }
+
+    #[test]
+    fn selects_by_sender() {
+        assert_eq!(
+            selected(&Selection::new(&[], &[], Some("+456"), None), messages()),
+            [3, 4, 5]
+        );
+        assert_eq!(
+            selected(
+                &Selection::new(&[2, 3], &[], Some("+123"), None),
+                messages()
+            ),
+            [2]
+        );
+    }
+
+    #[test]
+    fn selects_older_than_days() {
+        let mut messages = messages();
+
+        messages[0].timestamp = Some(NOW - Duration::days(2));
+        messages[1].timestamp = Some(NOW - Duration::days(2) + Duration::seconds(1));
+        messages[2].timestamp = Some(NOW - Duration::days(2) - Duration::seconds(1));
+        messages[3].timestamp = None;
+
+        // Message exactly N days old is not older than N days.
+        assert_eq!(
+            selected(&Selection::new(&[], &[], None, Some(2)), messages),
+            [3, 4]
+        );
+    }
+
+    #[test]
+    fn keeps_incomplete_messages_unless_selected_by_index() {
+        let mut messages = messages();
+
+        messages[2].indices = vec![3];
+        messages[2].total = 2;
+        messages[2].missing = vec![2];
+
+        let plan = Selection::new(&[], &[], None, None).plan(messages.clone(), NOW);
+
+        assert_eq!(indices(&plan.selected), [2, 5]);
+        assert_eq!(indices(&plan.kept), [3]);
+        assert_eq!(
+            selected(&Selection::new(&[3], &[], None, None), messages.clone()),
+            [3]
+        );
+        assert_eq!(
+            selected(&Selection::bulk(BulkDelete::Read), messages),
+            [2, 3]
+        );
+    }
+
+    #[test]
+    fn refuses_unarchived_messages() {
+        let mut plan = Selection::new(&[], &[], None, None).plan(messages(), NOW);
+
+        plan.refuse::<_, ()>(|message| Ok(message.indices != [5]))
+            .unwrap();
+
+        assert_eq!(indices(&plan.selected), [2, 3, 4]);
+        assert_eq!(indices(&plan.refused), [5]);
+
+        plan.refuse_all();
+
+        assert!(plan.selected.is_empty());
+        assert_eq!(indices(&plan.refused), [5, 2, 3, 4]);
+    }
+
+    #[test]
+    fn maps_bulk_modes_to_commands() {
+        for (mode, text, pdu) in [
+            (BulkDelete::Read, "AT+CMGD=1,1", "AT+CMGD=1,1"),
+            (BulkDelete::ReadSent, "AT+CMGD=1,2", "AT+CMGD=1,2"),
+            (BulkDelete::ReadSentUnsent, "AT+CMGD=1,3", "AT+CMGD=1,3"),
+            (BulkDelete::All, "AT+CMGD=1,4", "AT+CMGD=1,4"),
+            (BulkDelete::Unread, r#"AT+CMGDA="DEL UNREAD""#, "AT+CMGDA=2"),
+            (BulkDelete::Sent, r#"AT+CMGDA="DEL SENT""#, "AT+CMGDA=3"),
+            (BulkDelete::Unsent, r#"AT+CMGDA="DEL UNSENT""#, "AT+CMGDA=4"),
+            (BulkDelete::Inbox, r#"AT+CMGDA="DEL INBOX""#, "AT+CMGDA=5"),
+        ] {
+            assert_eq!(bulk_command(mode, false), text);
+            assert_eq!(bulk_command(mode, true), pdu);
+        }
+    }
+
+    #[test]
+    fn selects_bulk_mode_statuses() {
+        for (mode, expected) in [
+            (BulkDelete::Read, vec![2, 3, 4]),
+            (BulkDelete::ReadSent, vec![2, 3, 4, 5]),
+            (BulkDelete::ReadSentUnsent, vec![2, 3, 4, 5, 6]),
+            (BulkDelete::All, vec![1, 2, 3, 4, 5, 6]),
+            (BulkDelete::Unread, vec![1]),
+            (BulkDelete::Sent, vec![5]),
+            (BulkDelete::Unsent, vec![6]),
+            (BulkDelete::Inbox, vec![1, 2, 3, 4]),
+        ] {
+            assert_eq!(
+                selected(&Selection::bulk(mode), messages()),
+                expected,
+                "{:?}",
+                mode
+            );
+        }
+    }
+}
@@ -0,0 +1,338 @@
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Result as FmtResult;
+
+/// Generates error code enum with numeric code and description of every variant. Descriptions
+/// match verbose error text shown with `AT+CMEE=2`, so both forms are converted to the same
+/// variant. Codes missing in table are kept as `Unknown`, unknown verbose texts as `Other`.
+macro_rules! error_codes {
+    (
+        $(#[$meta:meta])*
+        $name:ident {
+            $($variant:ident = $code:literal => $description:literal,)*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, PartialEq, Eq, Hash, Clone)]
+        pub enum $name {
+            $($variant,)*
+            Unknown(u16),
+            Other(String),
+        }
+
+        impl $name {
+            pub fn from_code(code: u16) -> Self {
+                match code {
+                    $($code => Self::$variant,)*
+                    code => Self::Unknown(code),
+                }
+            }
+
+            pub fn from_message(message: &str) -> Self {
+                let message = message.trim();
+
+                $(
+                    if message.eq_ignore_ascii_case($description) {
+                        return Self::$variant;
+                    }
+                )*
+
+                Self::Other(message.into())
+            }
+
+            pub fn code(&self) -> Option<u16> {
+                match self {
+                    $(Self::$variant => Some($code),)*
+                    Self::Unknown(code) => Some(*code),
+                    Self::Other(_) => None,
+                }
+            }
+
+            pub fn description(&self) -> &str {
+                match self {
+                    $(Self::$variant => $description,)*
+                    Self::Unknown(_) => "unknown error",
+                    Self::Other(message) => message,
+                }
+            }
+        }
+
+        impl Display for $name {
+            fn fmt(&self, f: &mut Formatter) -> FmtResult {
+                match self.code() {
+                    Some(code) => write!(f, "{} ({})", self.description(), code),
+                    None => write!(f, "{}", self.description()),
+                }
+            }
+        }
+    };
+}
+
+error_codes! {
+    /// Mobile equipment errors (`+CME ERROR`), 3GPP TS 27.007 section 9.2 and SIM800 AT commands
+    /// manual.
+    EquipmentError {
+        PhoneFailure = 0 => "phone failure",
+        NoConnection = 1 => "no connection to phone",
+        AdaptorLinkReserved = 2 => "phone-adaptor link reserved",
+        OperationNotAllowed = 3 => "operation not allowed",
+        OperationNotSupported = 4 => "operation not supported",
+        PhoneSimPinRequired = 5 => "PH-SIM PIN required",
+        PhoneFirstSimPinRequired = 6 => "PH-FSIM PIN required",
+        PhoneFirstSimPukRequired = 7 => "PH-FSIM PUK required",
+        SimNotInserted = 10 => "SIM not inserted",
+        SimPinRequired = 11 => "SIM PIN required",
+        SimPukRequired = 12 => "SIM PUK required",
+        SimFailure = 13 => "SIM failure",
+        SimBusy = 14 => "SIM busy",
+        SimWrong = 15 => "SIM wrong",
+        IncorrectPassword = 16 => "incorrect password",
+        SimPin2Required = 17 => "SIM PIN2 required",
+        SimPuk2Required = 18 => "SIM PUK2 required",
+        MemoryFull = 20 => "memory full",
+        InvalidIndex = 21 => "invalid index",
+        NotFound = 22 => "not found",
+        MemoryFailure = 23 => "memory failure",
+        TextTooLong = 24 => "text string too long",
+        InvalidTextCharacters = 25 => "invalid characters in text string",
+        DialStringTooLong = 26 => "dial string too long",
+        InvalidDialCharacters = 27 => "invalid characters in dial string",
+        NoNetworkService = 30 => "no network service",
+        NetworkTimeout = 31 => "network timeout",
+        EmergencyCallsOnly = 32 => "network not allowed - emergency calls only",
+        NetworkPinRequired = 40 => "network personalization PIN required",
+        NetworkPukRequired = 41 => "network personalization PUK required",
+        NetworkSubsetPinRequired = 42 => "network subset personalization PIN required",
+        NetworkSubsetPukRequired = 43 => "network subset personalization PUK required",
+        ServiceProviderPinRequired = 44 => "service provider personalization PIN required",
+        ServiceProviderPukRequired = 45 => "service provider personalization PUK required",
+        CorporatePinRequired = 46 => "corporate personalization PIN required",
+        CorporatePukRequired = 47 => "corporate personalization PUK required",
+        HiddenKeyRequired = 48 => "hidden key required",
+        EapMethodNotSupported = 49 => "EAP method not supported",
+        IncorrectParameters = 50 => "incorrect parameters",
+        UnknownError = 100 => "unknown",
+        IllegalMobileStation = 103 => "illegal MS",
+        IllegalMobileEquipment = 106 => "illegal ME",
+        GprsNotAllowed = 107 => "GPRS services not allowed",
+        PlmnNotAllowed = 111 => "PLMN not allowed",
+        LocationAreaNotAllowed = 112 => "location area not allowed",
+        RoamingNotAllowed = 113 => "roaming not allowed in this location area",
+        ServiceOptionNotSupported = 132 => "service option not supported",
+        ServiceOptionNotSubscribed = 133 => "requested service option not subscribed",
+        ServiceOptionOutOfOrder = 134 => "service option temporarily out of order",
+        UnspecifiedGprsError = 148 => "unspecified GPRS error",
+        PdpAuthenticationFailure = 149 => "PDP authentication failure",
+        InvalidMobileClass = 150 => "invalid mobile class",
+        NetworkRejectedRequest = 257 => "network rejected request",
+        RetryOperation = 258 => "retry operation",
+        InvalidDeflectedNumber = 259 => "invalid deflected to number",
+        DeflectedToOwnNumber = 260 => "deflected to own number",
+        UnknownSubscriber = 261 => "unknown subscriber",
+        ServiceNotAvailable = 262 => "service not available",
+        UnknownClass = 263 => "unknown class specified",
+        UnknownNetworkMessage = 264 => "unknown network message",
+    }
+}
+
+error_codes! {
+    /// Message service errors (`+CMS ERROR`), 3GPP TS 27.005 section 3.2.5, 3GPP TS 24.011 and
+    /// 3GPP TS 23.040 causes and SIM800 AT commands manual.
+    ServiceError {
+        UnassignedNumber = 1 => "unassigned (unallocated) number",
+        OperatorBarring = 8 => "operator determined barring",
+        CallBarred = 10 => "call barred",
+        TransferRejected = 21 => "short message transfer rejected",
+        DestinationOutOfService = 27 => "destination out of service",
+        UnidentifiedSubscriber = 28 => "unidentified subscriber",
+        FacilityRejected = 29 => "facility rejected",
+        UnknownSubscriber = 30 => "unknown subscriber",
+        NetworkOutOfOrder = 38 => "network out of order",
+        TemporaryFailure = 41 => "temporary failure",
+        Congestion = 42 => "congestion",
+        ResourcesUnavailable = 47 => "resources unavailable, unspecified",
+        FacilityNotSubscribed = 50 => "requested facility not subscribed",
+        FacilityNotImplemented = 69 => "requested facility not implemented",
+        InvalidTransferReference = 81 => "invalid short message transfer reference value",
+        InvalidMessage = 95 => "invalid message, unspecified",
+        InvalidMandatoryInformation = 96 => "invalid mandatory information",
+        MessageTypeNotImplemented = 97 => "message type non-existent or not implemented",
+        MessageNotCompatible = 98 => "message not compatible with short message protocol state",
+        ElementNotImplemented = 99 => "information element non-existent or not implemented",
+        ProtocolError = 111 => "protocol error, unspecified",
+        Interworking = 127 => "interworking, unspecified",
+        TelematicInterworkingNotSupported = 128 => "telematic interworking not supported",
+        ShortMessageType0NotSupported = 129 => "short message type 0 not supported",
+        CannotReplaceMessage = 130 => "cannot replace short message",
+        UnspecifiedProtocolIdentifierError = 143 => "unspecified TP-PID error",
+        DataCodingNotSupported = 144 => "data coding scheme (alphabet) not supported",
+        MessageClassNotSupported = 145 => "message class not supported",
+        UnspecifiedDataCodingError = 159 => "unspecified TP-DCS error",
+        CommandCannotBeActioned = 160 => "command cannot be actioned",
+        CommandUnsupported = 161 => "command unsupported",
+        UnspecifiedCommandError = 175 => "unspecified TP-Command error",
+        TpduNotSupported = 176 => "TPDU not supported",
+        ServiceCenterBusy = 192 => "SC busy",
+        NoServiceCenterSubscription = 193 => "no SC subscription",
+        ServiceCenterFailure = 194 => "SC system failure",
+        InvalidSmeAddress = 195 => "invalid SME address",
+        DestinationSmeBarred = 196 => "destination SME barred",
+        DuplicateRejected = 197 => "SM rejected-duplicate SM",
+        ValidityFormatNotSupported = 198 => "TP-VPF not supported",
+        ValidityPeriodNotSupported = 199 => "TP-VP not supported",
+        SimStorageFull = 208 => "D0 SIM SMS storage full",
+        NoSimStorage = 209 => "no SMS storage capability in SIM",
+        MobileStationError = 210 => "error in MS",
+        MemoryCapacityExceeded = 211 => "memory capacity exceeded",
+        SimToolkitBusy = 212 => "SIM application toolkit busy",
+        SimDownloadError = 213 => "SIM data download error",
+        UnspecifiedCause = 255 => "unspecified error cause",
+        EquipmentFailure = 300 => "ME failure",
+        ServiceReserved = 301 => "SMS service of ME reserved",
+        OperationNotAllowed = 302 => "operation not allowed",
+        OperationNotSupported = 303 => "operation not supported",
+        InvalidPduParameter = 304 => "invalid PDU mode parameter",
+        InvalidTextParameter = 305 => "invalid text mode parameter",
+        SimNotInserted = 310 => "SIM not inserted",
+        SimPinRequired = 311 => "SIM PIN required",
+        PhoneSimPinRequired = 312 => "PH-SIM PIN required",
+        SimFailure = 313 => "SIM failure",
+        SimBusy = 314 => "SIM busy",
+        SimWrong = 315 => "SIM wrong",
+        SimPukRequired = 316 => "SIM PUK required",
+        SimPin2Required = 317 => "SIM PIN2 required",
+        SimPuk2Required = 318 => "SIM PUK2 required",
+        MemoryFailure = 320 => "memory failure",
+        InvalidMemoryIndex = 321 => "invalid memory index",
+        MemoryFull = 322 => "memory full",
+        ServiceCenterAddressUnknown = 330 => "SMSC address unknown",
+        NoNetworkService = 331 => "no network service",
+        NetworkTimeout = 332 => "network timeout",
+        NoAcknowledgementExpected = 340 => "no +CNMA acknowledgement expected",
+        UnknownError = 500 => "unknown error",
+        SimNotReady = 512 => "SIM not ready",
+        UnreadSimRecords = 513 => "unread records on SIM",
+        BroadcastUnknownError = 514 => "CB error unknown",
+        ProtocolStackBusy = 515 => "PS busy",
+        SmsBlockNotReady = 517 => "SM BL not ready",
+        InvalidPduCharacters = 528 => "invalid (non-hex) chars in PDU",
+        IncorrectPduLength = 529 => "incorrect PDU length",
+        InvalidMessageType = 530 => "invalid MTI",
+        InvalidAddressCharacters = 531 => "invalid (non-hex) chars in address",
+        InvalidAddress = 532 => "invalid address (no digits read)",
+        IncorrectUserDataLength = 533 => "incorrect PDU length (UDL)",
+        IncorrectServiceCenterLength = 534 => "incorrect SCA length",
+        InvalidFirstOctet = 536 => "invalid first octet (should be 2 or 34)",
+        InvalidCommandType = 537 => "invalid command type",
+        StatusReportNotRequested = 538 => "SRR bit not set",
+        StatusReportRequested = 539 => "SRR bit set",
+        InvalidHeaderElement = 540 => "invalid user data header IE",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse;
+    use crate::parser::CharacterSet;
+    use crate::parser::MobileEquipmentError;
+    use crate::parser::ParseResult;
+    use crate::parser::Response;
+
+    fn parse_error(line: &str) -> MobileEquipmentError {
+        match parse(&format!("{}\r\n", line), CharacterSet::Ira) {
+            ParseResult::Success {
+                response: Response::Error(error),
+                ..
+            } => error,
+            result => panic!("Unexpected result for {:?}: {:?}", line, result),
+        }
+    }
+
+    #[test]
+    fn maps_equipment_code_and_message_to_same_variant() {
+        for (code, message, expected) in [
+            (
+                3,
+                "operation not allowed",
+                EquipmentError::OperationNotAllowed,
+            ),
+            (10, "SIM not inserted", EquipmentError::SimNotInserted),
+            (30, "no network service", EquipmentError::NoNetworkService),
+            (100, "unknown", EquipmentError::UnknownError),
+        ] {
+            assert_eq!(EquipmentError::from_code(code), expected);
+            assert_eq!(EquipmentError::from_message(message), expected);
+            assert_eq!(
+                EquipmentError::from_message(&format!(" {} ", message.to_uppercase())),
+                expected
+            );
+            assert_eq!(expected.code(), Some(code));
+            assert_eq!(
+                parse_error(&format!("+CME ERROR: {}", code)),
+                parse_error(&format!("+CME ERROR: {}", message))
+            );
+        }
+    }
+
+    #[test]
+    fn maps_service_code_and_message_to_same_variant() {
+        for (code, message, expected) in [
+            (
+                302,
+                "operation not allowed",
+                ServiceError::OperationNotAllowed,
+            ),
+            (
+                304,
+                "invalid PDU mode parameter",
+                ServiceError::InvalidPduParameter,
+            ),
+            (
+                321,
+                "invalid memory index",
+                ServiceError::InvalidMemoryIndex,
+            ),
+            (500, "unknown error", ServiceError::UnknownError),
+        ] {
+            assert_eq!(ServiceError::from_code(code), expected);
+            assert_eq!(ServiceError::from_message(message), expected);
+            assert_eq!(expected.code(), Some(code));
+            assert_eq!(
+                parse_error(&format!("+CMS ERROR: {}", code)),
+                MobileEquipmentError::Service(expected.clone())
+            );
+            assert_eq!(
+                parse_error(&format!("+CMS ERROR: {}", message)),
+                MobileEquipmentError::Service(expected)
+            );
+        }
+    }
+
+    #[test]
+    fn keeps_unknown_codes() {
+        assert_eq!(EquipmentError::from_code(999), EquipmentError::Unknown(999));
+        assert_eq!(
+            ServiceError::from_message("strange failure"),
+            ServiceError::Other("strange failure".into())
+        );
+        assert_eq!(
+            ServiceError::Unknown(999).to_string(),
+            "unknown error (999)"
+        );
+        assert_eq!(ServiceError::Other("odd".into()).to_string(), "odd");
+        assert_eq!(
+            parse_error("+CMS ERROR: 999"),
+            MobileEquipmentError::Service(ServiceError::Unknown(999))
+        );
+        assert_eq!(
+            parse_error("+CME ERROR: strange failure"),
+            MobileEquipmentError::Equipment(EquipmentError::Other("strange failure".into()))
+        );
+        // Code out of range is not a number for parser, kept as text.
+        assert!(matches!(
+            parse_error("+CME ERROR: 70000"),
+            MobileEquipmentError::Equipment(EquipmentError::Other(_))
+        ));
+    }
+}
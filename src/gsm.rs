@@ -0,0 +1,253 @@
+use std::error::Error;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Result as FmtResult;
+
+/// Escape to extension table.
+pub const ESCAPE: u8 = 0x1B;
+
+/// GSM 7 bit default alphabet, 3GPP TS 23.038 section 6.2.1.
+const BASIC: [char; 128] = [
+    '@', '£', '$', '¥', 'è', 'é', 'ù', 'ì', 'ò', 'Ç', '\n', 'Ø', 'ø', '\r', 'Å', 'å', //
+    'Δ', '_', 'Φ', 'Γ', 'Λ', 'Ω', 'Π', 'Ψ', 'Σ', 'Θ', 'Ξ', '\u{A0}', 'Æ', 'æ', 'ß', 'É', //
+    ' ', '!', '"', '#', '¤', '%', '&', '\'', '(', ')', '*', '+', ',', '-', '.', '/', //
+    '0', '1', '2', '3', '4', '5', '6', '7', '8', '9', ':', ';', '<', '=', '>', '?', //
+    '¡', 'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I', 'J', 'K', 'L', 'M', 'N', 'O', //
+    'P', 'Q', 'R', 'S', 'T', 'U', 'V', 'W', 'X', 'Y', 'Z', 'Ä', 'Ö', 'Ñ', 'Ü', '§', //
+    '¿', 'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l', 'm', 'n', 'o', //
+    'p', 'q', 'r', 's', 't', 'u', 'v', 'w', 'x', 'y', 'z', 'ä', 'ö', 'ñ', 'ü', 'à', //
+];
+
+/// Default alphabet extension table, 3GPP TS 23.038 section 6.2.1.1.
+const EXTENSION: [(u8, char); 10] = [
+    (0x0A, '\u{0C}'),
+    (0x14, '^'),
+    (0x28, '{'),
+    (0x29, '}'),
+    (0x2F, '\\'),
+    (0x3C, '['),
+    (0x3D, '~'),
+    (0x3E, ']'),
+    (0x40, '|'),
+    (0x65, '€'),
+];
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub enum GsmError {
+    UnencodableCharacter(char),
+}
+
+impl Error for GsmError {}
+
+impl Display for GsmError {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            Self::UnencodableCharacter(ch) => {
+                write!(f, "Character {:?} is not in GSM 7 bit alphabet", ch)
+            }
+        }
+    }
+}
+
+/// Encodes single character, extension table characters take two septets.
+fn encode_char(ch: char, septets: &mut Vec<u8>) -> Result<(), GsmError> {
+    if let Some(septet) = BASIC
+        .iter()
+        .position(|&symbol| symbol == ch && symbol != '\u{A0}')
+    {
+        septets.push(septet as u8);
+    } else if let Some(&(septet, _)) = EXTENSION.iter().find(|&&(_, symbol)| symbol == ch) {
+        septets.push(ESCAPE);
+        septets.push(septet);
+    } else {
+        return Err(GsmError::UnencodableCharacter(ch));
+    }
+
+    Ok(())
+}
+
+/// Converts text to unpacked septets.
+pub fn encode(text: &str) -> Result<Vec<u8>, GsmError> {
+    let mut result = Vec::with_capacity(text.len());
+
+    for ch in text.chars() {
+        encode_char(ch, &mut result)?;
+    }
+
+    Ok(result)
+}
+
+/// Converts unpacked septets to text. Unknown extension characters are replaced with default
+/// alphabet characters as recommended by specification.
+pub fn decode(septets: &[u8]) -> String {
+    let mut result = String::with_capacity(septets.len());
+    let mut septets = septets.iter().map(|septet| septet & 0x7F);
+
+    while let Some(septet) = septets.next() {
+        if septet != ESCAPE {
+            result.push(BASIC[septet as usize]);
+        } else if let Some(septet) = septets.next() {
+            match EXTENSION.iter().find(|&&(code, _)| code == septet) {
+                Some(&(_, symbol)) => result.push(symbol),
+                None => result.push(BASIC[septet as usize]),
+            }
+        }
+    }
+
+    result
+}
+
+/// Checks that every character of text can be represented in GSM 7 bit alphabet.
+pub fn is_encodable(text: &str) -> bool {
+    let mut septets = Vec::new();
+
+    text.chars().all(|ch| encode_char(ch, &mut septets).is_ok())
+}
+
+/// Returns number of septets required to encode text.
+pub fn septet_count(text: &str) -> Result<usize, GsmError> {
+    encode(text).map(|septets| septets.len())
+}
+
+/// Encodes text for `AT+CSCS="GSM"` text mode where every septet is transferred as single byte.
+pub fn encode_text(text: &str) -> Result<String, GsmError> {
+    encode(text).map(|septets| septets.into_iter().map(char::from).collect())
+}
+
+/// Decodes text received in `AT+CSCS="GSM"` text mode.
+pub fn decode_text(text: &str) -> String {
+    let septets: Vec<u8> = text.chars().map(|ch| ch as u32 as u8).collect();
+
+    decode(&septets)
+}
+
+/// Packs septets into octets, `skip` septets at beginning are left blank (used for fill bits
+/// after user data header).
+pub fn pack(septets: &[u8], skip: usize) -> Vec<u8> {
+    let mut result = vec![0; ((skip + septets.len()) * 7).div_ceil(8)];
+
+    for (index, septet) in septets.iter().enumerate() {
+        let bit = (skip + index) * 7;
+        let value = ((septet & 0x7F) as u16) << (bit % 8);
+
+        result[bit / 8] |= value as u8;
+
+        if bit % 8 > 1 {
+            result[bit / 8 + 1] |= (value >> 8) as u8;
+        }
+    }
+
+    result
+}
+
+/// Unpacks `count` septets from octets skipping first `skip` septets.
+pub fn unpack(data: &[u8], skip: usize, count: usize) -> Option<Vec<u8>> {
+    (skip..skip + count)
+        .map(|index| {
+            let bit = index * 7;
+            let low = *data.get(bit / 8)? as u16;
+            let high = data.get(bit / 8 + 1).copied().unwrap_or(0) as u16;
+
+            Some(((high << 8 | low) >> (bit % 8)) as u8 & 0x7F)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_basic_alphabet() {
+        for (septet, &ch) in BASIC.iter().enumerate() {
+            let septet = septet as u8;
+
+            if septet == ESCAPE {
+                assert_eq!(decode(&[septet]), "");
+                assert!(!is_encodable(&ch.to_string()));
+
+                continue;
+            }
+
+            assert_eq!(decode(&[septet]), ch.to_string());
+            assert_eq!(encode(&ch.to_string()), Ok(vec![septet]));
+            assert_eq!(septet_count(&ch.to_string()), Ok(1));
+        }
+    }
+
+    #[test]
+    fn round_trips_extension_table() {
+        for &(septet, ch) in &EXTENSION {
+            assert_eq!(decode(&[ESCAPE, septet]), ch.to_string());
+            assert_eq!(encode(&ch.to_string()), Ok(vec![ESCAPE, septet]));
+            assert_eq!(septet_count(&ch.to_string()), Ok(2));
+        }
+    }
+
+    #[test]
+    fn decodes_unknown_extension_as_basic_character() {
+        assert_eq!(decode(&[ESCAPE, 0x41, 0x42]), "AB");
+    }
+
+    #[test]
+    fn round_trips_whole_alphabet() {
+        let text: String = BASIC
+            .iter()
+            .filter(|&&ch| ch != '\u{A0}')
+            .chain(EXTENSION.iter().map(|(_, ch)| ch))
+            .collect();
+
+        assert_eq!(decode(&encode(&text).unwrap()), text);
+        assert_eq!(decode_text(&encode_text(&text).unwrap()), text);
+    }
+
+    #[test]
+    fn checks_encodable_text() {
+        assert!(is_encodable(""));
+        assert!(is_encodable("Hello {world} €5 @ Ørsted"));
+        assert!(!is_encodable("Привет"));
+        assert!(!is_encodable("\u{A0}"));
+        assert!(!is_encodable("ok 👍"));
+        assert_eq!(encode("ok 👍"), Err(GsmError::UnencodableCharacter('👍')));
+    }
+
+    #[test]
+    fn packs_known_text() {
+        let septets = encode("hellohello").unwrap();
+
+        assert_eq!(
+            pack(&septets, 0),
+            [0xE8, 0x32, 0x9B, 0xFD, 0x46, 0x97, 0xD9, 0xEC, 0x37]
+        );
+    }
+
+    #[test]
+    fn round_trips_packing_with_skip() {
+        let septets: Vec<u8> = (0..128).collect();
+
+        for skip in 0..=6 {
+            for count in 0..=septets.len() {
+                let packed = pack(&septets[..count], skip);
+
+                assert_eq!(packed.len(), ((skip + count) * 7).div_ceil(8));
+                assert_eq!(
+                    unpack(&packed, skip, count).as_deref(),
+                    Some(&septets[..count]),
+                    "skip {}, count {}",
+                    skip,
+                    count
+                );
+                assert_eq!(unpack(&packed, skip, count + 2), None);
+            }
+        }
+    }
+
+    #[test]
+    fn leaves_skipped_septets_blank() {
+        for skip in 1..=6 {
+            let packed = pack(&[0x7F; 8], skip);
+
+            assert_eq!(unpack(&packed, 0, skip), Some(vec![0; skip]));
+        }
+    }
+}
@@ -0,0 +1,9 @@
+pub mod address;
+pub mod concat;
+pub mod error_code;
+pub mod gsm;
+pub mod parser;
+pub mod pdu;
+pub mod sim800;
+pub mod simulator;
+pub mod transport;
@@ -1,92 +1,194 @@
+mod archive;
 mod arguments;
-mod parser;
-mod sim800;
+mod commands;
+mod delete;
+mod output;
+mod terminal;
+mod watch;
 
+use archive::Archive;
+use archive::Query;
+use arguments::ArchiveCommand;
 use arguments::Arguments;
+use arguments::Command;
+use arguments::ModuleCommand;
+use arguments::SmsCommand;
+use clap::error::ErrorKind;
+use clap::CommandFactory;
 use clap::Parser;
-use log::error;
-use parser::{NetworkRegistrationStatus, Response};
-use sim800::Sim800;
+use delete::Selection;
+use output::Output;
+use sim800_reader::sim800::Sim800;
+use sim800_reader::transport::RecordTransport;
+use sim800_reader::transport::ReplayTransport;
+use sim800_reader::transport::SerialTransport;
+use sim800_reader::transport::TcpTransport;
+use sim800_reader::transport::Transport;
 use std::error::Error;
+use std::process::ExitCode;
 use std::time::Duration;
-use time::format_description::BorrowedFormatItem;
-use time::macros::format_description;
+use time::UtcOffset;
 
-use crate::parser::SmsMessageStatus;
+/// Port read timeout, limits time to stop reader thread.
+const READ_TIMEOUT: Duration = Duration::from_millis(500);
 
-const DATE_FORMAT: &[BorrowedFormatItem<'_>] =
-    format_description!("[year].[month].[day] [hour]:[minute]:[second]");
+/// Opens port selected in arguments and waits until module ready.
+fn connect(arguments: &Arguments) -> Result<Sim800, Box<dyn Error>> {
+    let Some(port) = arguments.serial_port() else {
+        <Arguments as CommandFactory>::command()
+            .error(
+                ErrorKind::MissingRequiredArgument,
+                "serial port required, select it with --serial-port",
+            )
+            .exit()
+    };
+    let mut transport: Box<dyn Transport> = if let Some(address) = port.strip_prefix("tcp://") {
+        Box::new(TcpTransport::connect(address, READ_TIMEOUT)?)
+    } else if let Some(path) = port.strip_prefix("replay://") {
+        Box::new(ReplayTransport::open(path, READ_TIMEOUT)?)
+    } else {
+        Box::new(SerialTransport::open(
+            port,
+            arguments.baud_rate(),
+            READ_TIMEOUT,
+        )?)
+    };
 
-fn main() -> Result<(), Box<dyn Error>> {
-    env_logger::init();
+    if let Some(path) = arguments.record() {
+        transport = Box::new(RecordTransport::create(transport, path)?);
+    }
 
-    let arguments = Arguments::parse();
-    let port = serialport::new(arguments.serial_port(), arguments.baud_rate())
-        .timeout(Duration::from_secs(arguments.timeout()))
-        .open()?;
-    let mut sim800 = Sim800::new(port)?;
-    sim800.send(r#"AT"#)?;
-
-    match sim800.send_one(r#"AT+CREG?"#)? {
-        Some(Response::NetworkRegistration {
-            status: NetworkRegistrationStatus::Registered,
-            ..
-        })
-        | Some(Response::NetworkRegistration {
-            status: NetworkRegistrationStatus::RegisteredRoaming,
-            ..
-        }) => println!("Module ready and registered in network."),
-        _ => println!("Not registered in network."),
+    let mut sim800 = Sim800::new(transport)?;
+    sim800.set_timeout(Duration::from_secs(arguments.timeout()));
+
+    let readiness = sim800.initialize(
+        arguments.fix_baud_rate().then_some(arguments.baud_rate()),
+        Duration::from_secs(arguments.ready_timeout()),
+    )?;
+
+    if !readiness.is_ready() {
+        eprintln!("Warning: module not ready, {}.", readiness);
     }
 
-    sim800.send(r#"AT+CMEE=2"#)?;
-    sim800.send(r#"AT+CMGF=1"#)?;
-    sim800.send(r#"AT+CSCS="UCS2""#)?;
+    if arguments.no_echo() {
+        sim800.set_echo(false)?;
+    }
 
-    if arguments.list_messages() {
-        for message in sim800.send_list(r#"AT+CMGL="ALL""#)? {
-            match message {
-                Response::ListSmsMessage {
-                    status,
+    Ok(sim800)
+}
+
+fn run(arguments: &Arguments, output: &Output) -> Result<ExitCode, Box<dyn Error>> {
+    let archive = arguments.archive().map(Archive::open).transpose()?;
+
+    let command = match arguments.command() {
+        Command::Module(command) => command,
+        Command::Archive { command } => {
+            let Some(archive) = &archive else {
+                <Arguments as CommandFactory>::command()
+                    .error(
+                        ErrorKind::MissingRequiredArgument,
+                        "archive file required, select it with --archive",
+                    )
+                    .exit()
+            };
+            let query = match command {
+                ArchiveCommand::Export => Query::default(),
+                ArchiveCommand::Search {
+                    text,
+                    from,
+                    newer_than,
+                } => Query {
+                    text: text.as_deref(),
+                    from: from.as_deref(),
+                    newer_than: *newer_than,
+                },
+            };
+
+            return commands::archive_search(archive, output, &query);
+        }
+    };
+
+    let archive = archive.as_ref();
+    let mut sim800 = connect(arguments)?;
+    let timeout = Duration::from_secs(arguments.timeout());
+    let result = match command {
+        ModuleCommand::Status => commands::status(&mut sim800)?,
+        ModuleCommand::Info => commands::info(&mut sim800)?,
+        ModuleCommand::Raw { commands } => commands::raw(&mut sim800, commands)?,
+        ModuleCommand::Terminal { history } => terminal::terminal(&mut sim800, history.as_deref())?,
+        ModuleCommand::Call { number, duration } => {
+            commands::call(&mut sim800, number, Duration::from_secs(*duration))?
+        }
+        ModuleCommand::Ussd { request } => commands::ussd(&mut sim800, request)?,
+        ModuleCommand::Sms {
+            pdu_mode,
+            character_set,
+            command,
+        } => {
+            commands::prepare_sms(&mut sim800, *pdu_mode, *character_set)?;
+
+            match command {
+                SmsCommand::List => commands::sms_list(&mut sim800, output, archive)?,
+                SmsCommand::Read { index } => {
+                    commands::sms_read(&mut sim800, output, archive, *index)?
+                }
+                SmsCommand::Send {
                     address,
-                    timestamp,
                     text,
-                    ..
+                    status_report,
                 } => {
-                    let mark = match status {
-                        SmsMessageStatus::ReceivedUnread => "<-",
-                        SmsMessageStatus::ReceivedRead | SmsMessageStatus::StoredUnsent => "--",
-                        SmsMessageStatus::StoredSent => "->",
+                    commands::sms_send(&mut sim800, output, address, text, *status_report, timeout)?
+                }
+                SmsCommand::Delete {
+                    indices,
+                    status,
+                    from,
+                    older_than,
+                    bulk,
+                    dry_run,
+                    force,
+                } => {
+                    let selection = match bulk {
+                        Some(mode) => Selection::bulk(*mode),
+                        None => Selection::new(indices, status, from.as_deref(), *older_than),
                     };
-
-                    println!(
-                        "{}: {} {} {}",
-                        timestamp.format(DATE_FORMAT)?,
-                        address,
-                        mark,
-                        text,
-                    );
+                    commands::sms_delete(
+                        &mut sim800,
+                        output,
+                        archive,
+                        &selection,
+                        *bulk,
+                        *dry_run,
+                        *force,
+                    )?
                 }
-                _ => {}
+                SmsCommand::Watch {
+                    delete,
+                    output: path,
+                } => commands::sms_watch(&mut sim800, output, archive, *delete, path.as_ref())?,
             }
         }
-    }
+    };
 
-    if arguments.delete_messages() {
-        for message in sim800.send_list(r#"AT+CMGL="ALL""#)? {
-            match message {
-                Response::ListSmsMessage { index, status, .. }
-                    if status == SmsMessageStatus::ReceivedRead
-                        || status == SmsMessageStatus::StoredSent =>
-                {
-                    sim800.send(&format!(r#"AT+CMGD={},0"#, index))?
-                }
-                _ => {}
-            }
-        }
+    sim800.join()?;
 
-        println!("All SMS messages removed.");
-    }
+    Ok(result)
+}
+
+fn main() -> ExitCode {
+    env_logger::init();
+
+    let arguments = Arguments::parse();
+    // Local offset can be determined only while process is single threaded.
+    let local_offset = UtcOffset::current_local_offset().unwrap_or(UtcOffset::UTC);
+    let output = Output::new(arguments.format(), arguments.time_zone(), local_offset);
+
+    match run(&arguments, &output) {
+        Ok(code) => code,
+        Err(error) => {
+            eprintln!("Error: {}", error);
 
-    Ok(())
+            commands::exit_code(error.as_ref())
+        }
+    }
 }
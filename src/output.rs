@@ -0,0 +1,371 @@
+use crate::arguments::OutputFormat;
+use crate::arguments::TimeZone;
+use sim800_reader::concat::SmsMessage;
+use sim800_reader::parser::SmsMessageStatus;
+use sim800_reader::parser::SmsMessageStorage;
+use std::error::Error;
+use std::fmt::Write;
+use time::format_description::well_known::Rfc3339;
+use time::format_description::BorrowedFormatItem;
+use time::macros::format_description;
+use time::OffsetDateTime;
+use time::UtcOffset;
+
+const DATE_FORMAT: &[BorrowedFormatItem<'_>] =
+    format_description!("[year].[month].[day] [hour]:[minute]:[second]");
+
+const CSV_HEADER: &str = "indices,status,address,address_text,timestamp,text,storage,total,missing";
+
+/// Formats messages in selected output format and time zone.
+#[derive(Debug, Clone, Copy)]
+pub struct Output {
+    format: OutputFormat,
+    time_zone: TimeZone,
+    local_offset: UtcOffset,
+}
+
+impl Output {
+    pub fn new(format: OutputFormat, time_zone: TimeZone, local_offset: UtcOffset) -> Self {
+        Self {
+            format,
+            time_zone,
+            local_offset,
+        }
+    }
+
+    /// Checks that output is intended for scripts, status messages should not be mixed with it.
+    pub fn is_structured(&self) -> bool {
+        !matches!(self.format, OutputFormat::Text)
+    }
+
+    /// Formats all listed messages, JSON format produces single array.
+    pub fn format_list(
+        &self,
+        messages: &[SmsMessage],
+        storage: Option<&SmsMessageStorage>,
+    ) -> Result<String, Box<dyn Error>> {
+        let mut result = String::new();
+
+        match self.format {
+            OutputFormat::Json => {
+                result.push('[');
+
+                for (index, message) in messages.iter().enumerate() {
+                    if index > 0 {
+                        result.push(',');
+                    }
+
+                    write!(result, "\n  {}", self.format_json(message, storage)?)?;
+                }
+
+                if !messages.is_empty() {
+                    result.push('\n');
+                }
+
+                result.push_str("]\n");
+            }
+            OutputFormat::Csv => {
+                writeln!(result, "{}", CSV_HEADER)?;
+
+                for message in messages {
+                    writeln!(result, "{}", self.format_message(message, storage)?)?;
+                }
+            }
+            OutputFormat::Ndjson | OutputFormat::Text => {
+                for message in messages {
+                    writeln!(result, "{}", self.format_message(message, storage)?)?;
+                }
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Returns line written before message stream.
+    pub fn header(&self) -> Option<&'static str> {
+        match self.format {
+            OutputFormat::Csv => Some(CSV_HEADER),
+            _ => None,
+        }
+    }
+
+    /// Formats single message as one line, JSON format produces the same line as NDJSON.
+    pub fn format_message(
+        &self,
+        message: &SmsMessage,
+        storage: Option<&SmsMessageStorage>,
+    ) -> Result<String, Box<dyn Error>> {
+        match self.format {
+            OutputFormat::Text => self.format_text(message),
+            OutputFormat::Json | OutputFormat::Ndjson => self.format_json(message, storage),
+            OutputFormat::Csv => self.format_csv(message, storage),
+        }
+    }
+
+    fn convert(&self, timestamp: OffsetDateTime) -> OffsetDateTime {
+        match self.time_zone {
+            TimeZone::Local => timestamp.to_offset(self.local_offset),
+            TimeZone::Utc => timestamp.to_offset(UtcOffset::UTC),
+            TimeZone::Original => timestamp,
+        }
+    }
+
+    fn format_text(&self, message: &SmsMessage) -> Result<String, Box<dyn Error>> {
+        let mark = match message.status {
+            SmsMessageStatus::ReceivedUnread => "<-",
+            SmsMessageStatus::ReceivedRead | SmsMessageStatus::StoredUnsent => "--",
+            SmsMessageStatus::StoredSent => "->",
+        };
+        let timestamp = match message.timestamp {
+            Some(timestamp) => self.convert(timestamp).format(DATE_FORMAT)?,
+            None => "-".into(),
+        };
+
+        if message.is_complete() {
+            Ok(format!(
+                "{}: {} {} {}",
+                timestamp, message.address, mark, message.text
+            ))
+        } else {
+            Ok(format!(
+                "{}: {} {} {} (incomplete, missing parts {:?} of {})",
+                timestamp, message.address, mark, message.text, message.missing, message.total,
+            ))
+        }
+    }
+
+    fn format_json(
+        &self,
+        message: &SmsMessage,
+        storage: Option<&SmsMessageStorage>,
+    ) -> Result<String, Box<dyn Error>> {
+        let timestamp = match message.timestamp {
+            Some(timestamp) => json_string(&self.convert(timestamp).format(&Rfc3339)?),
+            None => "null".into(),
+        };
+
+        Ok(format!(
+            r#"{{"indices":{},"status":{},"address":{},"address_text":{},"timestamp":{},"text":{},"storage":{},"total":{},"missing":{}}}"#,
+            json_numbers(&message.indices),
+            json_string(status_name(&message.status)),
+            json_string(&message.address.to_string()),
+            json_optional(message.address_text.as_deref()),
+            timestamp,
+            json_string(&message.text),
+            json_optional(storage.map(ToString::to_string).as_deref()),
+            message.total,
+            json_numbers(&message.missing),
+        ))
+    }
+
+    fn format_csv(
+        &self,
+        message: &SmsMessage,
+        storage: Option<&SmsMessageStorage>,
+    ) -> Result<String, Box<dyn Error>> {
+        let timestamp = match message.timestamp {
+            Some(timestamp) => self.convert(timestamp).format(&Rfc3339)?,
+            None => String::new(),
+        };
+        let fields = [
+            join_numbers(&message.indices),
+            status_name(&message.status).into(),
+            message.address.to_string(),
+            message.address_text.clone().unwrap_or_default(),
+            timestamp,
+            message.text.clone(),
+            storage.map(ToString::to_string).unwrap_or_default(),
+            message.total.to_string(),
+            join_numbers(&message.missing),
+        ];
+
+        Ok(fields
+            .iter()
+            .map(|field| csv_field(field))
+            .collect::<Vec<_>>()
+            .join(","))
+    }
+}
+
+fn status_name(status: &SmsMessageStatus) -> &'static str {
+    match status {
+        SmsMessageStatus::ReceivedUnread => "received_unread",
+        SmsMessageStatus::ReceivedRead => "received_read",
+        SmsMessageStatus::StoredUnsent => "stored_unsent",
+        SmsMessageStatus::StoredSent => "stored_sent",
+    }
+}
+
+/// Escapes string as JSON string literal, RFC 8259 section 7.
+fn json_string(text: &str) -> String {
+    let mut result = String::with_capacity(text.len() + 2);
+
+    result.push('"');
+
+    for c in text.chars() {
+        match c {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(result, "\\u{:04x}", c as u32);
+            }
+            c => result.push(c),
+        }
+    }
+
+    result.push('"');
+    result
+}
+
+fn json_optional(text: Option<&str>) -> String {
+    text.map(json_string).unwrap_or_else(|| "null".into())
+}
+
+fn json_numbers<T>(values: &[T]) -> String
+where
+    T: ToString,
+{
+    format!("[{}]", join_numbers_with(values, ","))
+}
+
+fn join_numbers<T>(values: &[T]) -> String
+where
+    T: ToString,
+{
+    join_numbers_with(values, " ")
+}
+
+fn join_numbers_with<T>(values: &[T], separator: &str) -> String
+where
+    T: ToString,
+{
+    values
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+/// Quotes CSV field containing separators, quotes or line breaks, RFC 4180.
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sim800_reader::address::Address;
+    use time::macros::datetime;
+    use time::macros::offset;
+
+    fn message(text: &str) -> SmsMessage {
+        SmsMessage {
+            indices: vec![1, 2],
+            status: SmsMessageStatus::ReceivedRead,
+            address: Address::parse("+123"),
+            address_text: None,
+            timestamp: Some(datetime!(2023-12-31 23:30:00 +2)),
+            text: text.into(),
+            total: 2,
+            missing: vec![],
+        }
+    }
+
+    #[test]
+    fn quotes_csv_fields() {
+        for (text, expected) in [
+            ("plain", "plain"),
+            ("", ""),
+            ("a,b", r#""a,b""#),
+            (r#"say "hi""#, r#""say ""hi""""#),
+            ("line\nbreak", "\"line\nbreak\""),
+            ("line\r\nbreak", "\"line\r\nbreak\""),
+            ("carriage\rreturn", "\"carriage\rreturn\""),
+        ] {
+            assert_eq!(csv_field(text), expected, "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn escapes_json_strings() {
+        for (text, expected) in [
+            ("plain", r#""plain""#),
+            (r#"quote " and \ slash"#, r#""quote \" and \\ slash""#),
+            ("\n\r\t", r#""\n\r\t""#),
+            ("\u{0}\u{1b}\u{7f}", r#""\u0000\u001b\u007f""#),
+            ("\u{85}", r#""\u0085""#),
+            ("привет 😀", "\"привет 😀\""),
+        ] {
+            assert_eq!(json_string(text), expected, "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn formats_timestamp_with_offset() {
+        let local = offset!(-5);
+
+        for (time_zone, format, expected) in [
+            (
+                TimeZone::Original,
+                OutputFormat::Text,
+                "2023.12.31 23:30:00",
+            ),
+            (TimeZone::Utc, OutputFormat::Text, "2023.12.31 21:30:00"),
+            (TimeZone::Local, OutputFormat::Text, "2023.12.31 16:30:00"),
+            (
+                TimeZone::Original,
+                OutputFormat::Csv,
+                "2023-12-31T23:30:00+02:00",
+            ),
+            (TimeZone::Utc, OutputFormat::Csv, "2023-12-31T21:30:00Z"),
+            (
+                TimeZone::Local,
+                OutputFormat::Csv,
+                "2023-12-31T16:30:00-05:00",
+            ),
+            (
+                TimeZone::Original,
+                OutputFormat::Json,
+                r#""2023-12-31T23:30:00+02:00""#,
+            ),
+        ] {
+            let output = Output::new(format, time_zone, local);
+            let line = output.format_message(&message("hello"), None).unwrap();
+
+            assert!(line.contains(expected), "{:?} {:?}", time_zone, line);
+        }
+    }
+
+    #[test]
+    fn formats_csv_message() {
+        let output = Output::new(OutputFormat::Csv, TimeZone::Original, UtcOffset::UTC);
+
+        assert_eq!(
+            output
+                .format_message(&message("a,\"b\"\nc"), Some(&SmsMessageStorage::Sim))
+                .unwrap(),
+            "1 2,received_read,+123,,2023-12-31T23:30:00+02:00,\"a,\"\"b\"\"\nc\",SM,2,"
+        );
+    }
+
+    #[test]
+    fn formats_json_message() {
+        let output = Output::new(OutputFormat::Ndjson, TimeZone::Original, UtcOffset::UTC);
+        let mut message = message("tab\there");
+
+        message.timestamp = None;
+        message.missing = vec![2];
+
+        assert_eq!(
+            output.format_message(&message, None).unwrap(),
+            r#"{"indices":[1,2],"status":"received_read","address":"+123","address_text":null,"timestamp":null,"text":"tab\there","storage":null,"total":2,"missing":[2]}"#
+        );
+    }
+}
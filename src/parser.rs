@@ -1,43 +1,154 @@
 use std::error::Error;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Result as FmtResult;
+use std::str::FromStr;
 
 use nom::branch::alt;
 use nom::bytes::streaming::is_not;
 use nom::bytes::streaming::tag;
+use nom::character::streaming::alphanumeric1;
 use nom::character::streaming::char;
-use nom::character::streaming::hex_digit0;
+use nom::character::streaming::hex_digit1;
 use nom::character::streaming::i16;
 use nom::character::streaming::i8;
+use nom::character::streaming::space0;
 use nom::character::streaming::u16;
 use nom::character::streaming::u8;
 use nom::combinator::map;
 use nom::combinator::map_res;
 use nom::combinator::opt;
+use nom::combinator::recognize;
 use nom::error::Error as NomError;
 use nom::sequence::delimited;
+use nom::sequence::preceded;
+use nom::sequence::terminated;
 use nom::Err;
 use nom::Parser;
 use time::Date;
 use time::Month;
 use time::OffsetDateTime;
+use time::PrimitiveDateTime;
 use time::Time;
+use time::UtcOffset;
+
+use crate::address::Address;
+use crate::error_code::EquipmentError;
+use crate::error_code::ServiceError;
+use crate::gsm;
+use crate::gsm::GsmError;
+use crate::pdu::Pdu;
+use crate::pdu::PduError;
+
+/// Unsolicited result codes without parser of their own, sent after power on and on supply
+/// voltage changes.
+const UNSOLICITED_CODES: [&str; 6] = [
+    "RDY",
+    "NORMAL POWER DOWN",
+    "UNDER-VOLTAGE POWER DOWN",
+    "UNDER-VOLTAGE WARNNING",
+    "OVER-VOLTAGE POWER DOWN",
+    "OVER-VOLTAGE WARNNING",
+];
+
+/// TE character set selected with `AT+CSCS`, used for text mode strings.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default)]
+pub enum CharacterSet {
+    Gsm,
+    #[default]
+    Ira,
+    Ucs2,
+}
+
+impl CharacterSet {
+    pub fn decode(&self, text: &str) -> String {
+        match self {
+            Self::Gsm => gsm::decode_text(text),
+            Self::Ira => text.into(),
+            Self::Ucs2 => ucs2_to_uft8(text),
+        }
+    }
 
-#[derive(Debug, PartialEq, Eq, Hash, Clone)]
-pub enum EquipmentErrorSource {
-    Mobile,
-    Service,
+    pub fn encode(&self, text: &str) -> Result<String, GsmError> {
+        match self {
+            Self::Gsm => gsm::encode_text(text),
+            Self::Ira => match text.chars().find(|ch| !ch.is_ascii()) {
+                Some(ch) => Err(GsmError::UnencodableCharacter(ch)),
+                None => Ok(text.into()),
+            },
+            Self::Ucs2 => Ok(utf8_to_ucs2(text)),
+        }
+    }
 }
 
+impl FromStr for CharacterSet {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_uppercase().as_str() {
+            "GSM" => Ok(Self::Gsm),
+            "IRA" => Ok(Self::Ira),
+            "UCS2" => Ok(Self::Ucs2),
+            _ => Err(format!("Unsupported character set: {}", value)),
+        }
+    }
+}
+
+impl Display for CharacterSet {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            Self::Gsm => write!(f, "GSM"),
+            Self::Ira => write!(f, "IRA"),
+            Self::Ucs2 => write!(f, "UCS2"),
+        }
+    }
+}
+
+/// Final result code of failed command, error codes are shown only if enabled with `AT+CMEE`.
 #[derive(Debug, PartialEq, Eq, Hash, Clone)]
 pub enum MobileEquipmentError {
     Disabled,
-    Code {
-        source: EquipmentErrorSource,
-        code: u16,
-    },
-    Message {
-        source: EquipmentErrorSource,
-        message: String,
-    },
+    Equipment(EquipmentError),
+    Service(ServiceError),
+}
+
+impl Error for MobileEquipmentError {}
+
+impl Display for MobileEquipmentError {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            Self::Disabled => write!(f, "ERROR"),
+            Self::Equipment(error) => write!(f, "+CME ERROR: {}", error),
+            Self::Service(error) => write!(f, "+CMS ERROR: {}", error),
+        }
+    }
+}
+
+/// Response field value not defined in specification.
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct InvalidValue {
+    field: &'static str,
+    value: String,
+}
+
+impl InvalidValue {
+    fn new<T>(field: &'static str, value: T) -> Self
+    where
+        T: ToString,
+    {
+        Self {
+            field,
+            value: value.to_string(),
+        }
+    }
+}
+
+impl Error for InvalidValue {}
+
+impl Display for InvalidValue {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "Invalid {}: {}", self.field, self.value)
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone)]
@@ -47,13 +158,15 @@ pub enum OperatorSelectionMode {
     Unknown,
 }
 
-impl From<u8> for OperatorSelectionMode {
-    fn from(value: u8) -> Self {
+impl TryFrom<u8> for OperatorSelectionMode {
+    type Error = InvalidValue;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
         match value {
-            0 => Self::Automatic,
-            1 => Self::Manual,
-            2 | 3 | 4 => Self::Unknown,
-            _ => unreachable!(),
+            0 => Ok(Self::Automatic),
+            1 => Ok(Self::Manual),
+            2..=4 => Ok(Self::Unknown),
+            _ => Err(InvalidValue::new("operator selection mode", value)),
         }
     }
 }
@@ -65,13 +178,15 @@ pub enum OperatorSelectionFormat {
     Numeric,
 }
 
-impl From<u8> for OperatorSelectionFormat {
-    fn from(value: u8) -> Self {
+impl TryFrom<u8> for OperatorSelectionFormat {
+    type Error = InvalidValue;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
         match value {
-            0 => Self::LongAlphanumeric,
-            1 => Self::ShortAlphanumeric,
-            2 => Self::Numeric,
-            _ => unreachable!(),
+            0 => Ok(Self::LongAlphanumeric),
+            1 => Ok(Self::ShortAlphanumeric),
+            2 => Ok(Self::Numeric),
+            _ => Err(InvalidValue::new("operator format", value)),
         }
     }
 }
@@ -83,13 +198,15 @@ pub enum NetworkRegistrationMode {
     EnableLocationRegistration,
 }
 
-impl From<u8> for NetworkRegistrationMode {
-    fn from(value: u8) -> Self {
+impl TryFrom<u8> for NetworkRegistrationMode {
+    type Error = InvalidValue;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
         match value {
-            0 => Self::DisableRegistration,
-            1 => Self::EnableRegistration,
-            2 => Self::EnableLocationRegistration,
-            _ => unreachable!(),
+            0 => Ok(Self::DisableRegistration),
+            1 => Ok(Self::EnableRegistration),
+            2 => Ok(Self::EnableLocationRegistration),
+            _ => Err(InvalidValue::new("network registration mode", value)),
         }
     }
 }
@@ -100,7 +217,9 @@ pub enum NetworkRegistrationStatus {
     Registered,
     SearchingOperator,
     RegistratonDenied,
-    Unknown,
+    /// Unknown status or status not defined in 3GPP TS 27.007 used by SIM800, like SMS only
+    /// registration.
+    Unknown(u8),
     RegisteredRoaming,
 }
 
@@ -111,9 +230,8 @@ impl From<u8> for NetworkRegistrationStatus {
             1 => Self::Registered,
             2 => Self::SearchingOperator,
             3 => Self::RegistratonDenied,
-            4 => Self::Unknown,
             5 => Self::RegisteredRoaming,
-            _ => unreachable!("Invalid network registration status: {}", value),
+            _ => Self::Unknown(value),
         }
     }
 }
@@ -121,7 +239,9 @@ impl From<u8> for NetworkRegistrationStatus {
 #[derive(Debug, PartialEq, Eq, Hash, Clone)]
 pub enum PhoneActivityStatus {
     Ready,
-    Unknown,
+    Unavailable,
+    /// Unknown status or status not used by SIM800, like asleep or manufacturer specific.
+    Unknown(u8),
     Ringing,
     CallInProgress,
 }
@@ -130,10 +250,56 @@ impl From<u8> for PhoneActivityStatus {
     fn from(value: u8) -> Self {
         match value {
             0 => Self::Ready,
-            2 => Self::Unknown,
+            1 => Self::Unavailable,
             3 => Self::Ringing,
             4 => Self::CallInProgress,
-            _ => unreachable!(),
+            _ => Self::Unknown(value),
+        }
+    }
+}
+
+/// SIM card state reported by `AT+CPIN?`, password required before card can be used.
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub enum PinStatus {
+    Ready,
+    SimPin,
+    SimPuk,
+    PhoneSimPin,
+    PhoneSimPuk,
+    SimPin2,
+    SimPuk2,
+    NotInserted,
+    Unknown(String),
+}
+
+impl Display for PinStatus {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            Self::Ready => write!(f, "READY"),
+            Self::SimPin => write!(f, "SIM PIN"),
+            Self::SimPuk => write!(f, "SIM PUK"),
+            Self::PhoneSimPin => write!(f, "PH_SIM PIN"),
+            Self::PhoneSimPuk => write!(f, "PH_SIM PUK"),
+            Self::SimPin2 => write!(f, "SIM PIN2"),
+            Self::SimPuk2 => write!(f, "SIM PUK2"),
+            Self::NotInserted => write!(f, "NOT INSERTED"),
+            Self::Unknown(status) => write!(f, "{}", status),
+        }
+    }
+}
+
+impl From<&str> for PinStatus {
+    fn from(value: &str) -> Self {
+        match value {
+            "READY" => Self::Ready,
+            "SIM PIN" => Self::SimPin,
+            "SIM PUK" => Self::SimPuk,
+            "PH_SIM PIN" => Self::PhoneSimPin,
+            "PH_SIM PUK" => Self::PhoneSimPuk,
+            "SIM PIN2" => Self::SimPin2,
+            "SIM PUK2" => Self::SimPuk2,
+            "NOT INSERTED" => Self::NotInserted,
+            status => Self::Unknown(status.into()),
         }
     }
 }
@@ -144,15 +310,17 @@ pub enum SignalQualityRssi {
     Unknown,
 }
 
-impl From<u8> for SignalQualityRssi {
-    fn from(value: u8) -> Self {
+impl TryFrom<u8> for SignalQualityRssi {
+    type Error = InvalidValue;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
         match value {
-            0 => Self::Value(-115),
-            1 => Self::Value(-111),
-            31 => Self::Value(-52),
-            99 => Self::Unknown,
-            2..=30 => Self::Value(-110 + value as i8 * 2 - 4),
-            _ => unreachable!(),
+            0 => Ok(Self::Value(-115)),
+            1 => Ok(Self::Value(-111)),
+            31 => Ok(Self::Value(-52)),
+            99 => Ok(Self::Unknown),
+            2..=30 => Ok(Self::Value(-110 + value as i8 * 2 - 4)),
+            _ => Err(InvalidValue::new("signal strength", value)),
         }
     }
 }
@@ -163,19 +331,21 @@ pub enum SignalQualityErrorRate {
     Unknown,
 }
 
-impl From<u8> for SignalQualityErrorRate {
-    fn from(value: u8) -> Self {
+impl TryFrom<u8> for SignalQualityErrorRate {
+    type Error = InvalidValue;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
         match value {
-            0 => Self::Value(0),
-            1 => Self::Value(2),
-            2 => Self::Value(4),
-            3 => Self::Value(8),
-            4 => Self::Value(16),
-            5 => Self::Value(32),
-            6 => Self::Value(64),
-            7 => Self::Value(128),
-            99 => Self::Unknown,
-            _ => unreachable!(),
+            0 => Ok(Self::Value(0)),
+            1 => Ok(Self::Value(2)),
+            2 => Ok(Self::Value(4)),
+            3 => Ok(Self::Value(8)),
+            4 => Ok(Self::Value(16)),
+            5 => Ok(Self::Value(32)),
+            6 => Ok(Self::Value(64)),
+            7 => Ok(Self::Value(128)),
+            99 => Ok(Self::Unknown),
+            _ => Err(InvalidValue::new("bit error rate", value)),
         }
     }
 }
@@ -187,13 +357,15 @@ pub enum BatteryChargeStatus {
     Finished,
 }
 
-impl From<u8> for BatteryChargeStatus {
-    fn from(value: u8) -> Self {
+impl TryFrom<u8> for BatteryChargeStatus {
+    type Error = InvalidValue;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
         match value {
-            0 => Self::NotCharging,
-            1 => Self::Charging,
-            2 => Self::Finished,
-            _ => unreachable!(),
+            0 => Ok(Self::NotCharging),
+            1 => Ok(Self::Charging),
+            2 => Ok(Self::Finished),
+            _ => Err(InvalidValue::new("battery charge status", value)),
         }
     }
 }
@@ -206,14 +378,30 @@ pub enum SmsMessageStatus {
     StoredSent,
 }
 
-impl From<&str> for SmsMessageStatus {
-    fn from(value: &str) -> Self {
+impl TryFrom<&str> for SmsMessageStatus {
+    type Error = InvalidValue;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "REC UNREAD" => Ok(Self::ReceivedUnread),
+            "REC READ" => Ok(Self::ReceivedRead),
+            "STO UNSENT" => Ok(Self::StoredUnsent),
+            "STO SENT" => Ok(Self::StoredSent),
+            _ => Err(InvalidValue::new("message status", value)),
+        }
+    }
+}
+
+impl TryFrom<u8> for SmsMessageStatus {
+    type Error = InvalidValue;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
         match value {
-            "REC UNREAD" => Self::ReceivedUnread,
-            "REC READ" => Self::ReceivedRead,
-            "STO UNSEND" => Self::StoredUnsent,
-            "STO SEND" => Self::StoredSent,
-            _ => unreachable!(),
+            0 => Ok(Self::ReceivedUnread),
+            1 => Ok(Self::ReceivedRead),
+            2 => Ok(Self::StoredUnsent),
+            3 => Ok(Self::StoredSent),
+            _ => Err(InvalidValue::new("message status", value)),
         }
     }
 }
@@ -222,6 +410,18 @@ impl From<&str> for SmsMessageStatus {
 pub enum SmsMessageStorage {
     Sim,
     Phone,
+    /// Storage not used by reader, like `MT` (SIM and phone) or `SR` (status reports).
+    Unknown(String),
+}
+
+impl Display for SmsMessageStorage {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            Self::Sim => write!(f, "SM"),
+            Self::Phone => write!(f, "ME"),
+            Self::Unknown(storage) => write!(f, "{}", storage),
+        }
+    }
 }
 
 impl From<&str> for SmsMessageStorage {
@@ -229,7 +429,7 @@ impl From<&str> for SmsMessageStorage {
         match value {
             "SM" => Self::Sim,
             "ME" => Self::Phone,
-            _ => unreachable!(),
+            storage => Self::Unknown(storage.into()),
         }
     }
 }
@@ -240,12 +440,14 @@ pub enum CallDirection {
     MobileTerminated,
 }
 
-impl From<u8> for CallDirection {
-    fn from(value: u8) -> Self {
+impl TryFrom<u8> for CallDirection {
+    type Error = InvalidValue;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
         match value {
-            0 => Self::MobileOriginated,
-            1 => Self::MobileTerminated,
-            _ => unreachable!(),
+            0 => Ok(Self::MobileOriginated),
+            1 => Ok(Self::MobileTerminated),
+            _ => Err(InvalidValue::new("call direction", value)),
         }
     }
 }
@@ -261,17 +463,19 @@ pub enum CallState {
     Disconnect,
 }
 
-impl From<u8> for CallState {
-    fn from(value: u8) -> Self {
+impl TryFrom<u8> for CallState {
+    type Error = InvalidValue;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
         match value {
-            0 => Self::Active,
-            1 => Self::Held,
-            2 => Self::Dialing,
-            3 => Self::Alerting,
-            4 => Self::Incoming,
-            5 => Self::Waiting,
-            6 => Self::Disconnect,
-            _ => unreachable!(),
+            0 => Ok(Self::Active),
+            1 => Ok(Self::Held),
+            2 => Ok(Self::Dialing),
+            3 => Ok(Self::Alerting),
+            4 => Ok(Self::Incoming),
+            5 => Ok(Self::Waiting),
+            6 => Ok(Self::Disconnect),
+            _ => Err(InvalidValue::new("call state", value)),
         }
     }
 }
@@ -283,13 +487,15 @@ pub enum CallMode {
     Fax,
 }
 
-impl From<u8> for CallMode {
-    fn from(value: u8) -> Self {
+impl TryFrom<u8> for CallMode {
+    type Error = InvalidValue;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
         match value {
-            0 => Self::Voice,
-            1 => Self::Data,
-            2 => Self::Fax,
-            _ => unreachable!(),
+            0 => Ok(Self::Voice),
+            1 => Ok(Self::Data),
+            2 => Ok(Self::Fax),
+            _ => Err(InvalidValue::new("call mode", value)),
         }
     }
 }
@@ -300,12 +506,14 @@ pub enum CallMultiparty {
     Multiparty,
 }
 
-impl From<u8> for CallMultiparty {
-    fn from(value: u8) -> Self {
+impl TryFrom<u8> for CallMultiparty {
+    type Error = InvalidValue;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
         match value {
-            0 => Self::NoMultiparty,
-            1 => Self::Multiparty,
-            _ => unreachable!(),
+            0 => Ok(Self::NoMultiparty),
+            1 => Ok(Self::Multiparty),
+            _ => Err(InvalidValue::new("call multiparty", value)),
         }
     }
 }
@@ -328,6 +536,9 @@ pub enum Response {
     PhoneActivityStatus {
         status: PhoneActivityStatus,
     },
+    PinStatus {
+        status: PinStatus,
+    },
     SignalQuality {
         rssi: SignalQualityRssi,
         error_rate: SignalQualityErrorRate,
@@ -340,22 +551,29 @@ pub enum Response {
     ListSmsMessage {
         index: u16,
         status: SmsMessageStatus,
-        address: String,
+        address: Address,
         address_text: Option<String>,
-        timestamp: OffsetDateTime,
+        timestamp: Option<OffsetDateTime>,
         text: String,
+        pdu: Option<Box<Pdu>>,
     },
     ReadSmsMessage {
         status: SmsMessageStatus,
-        address: String,
+        address: Address,
         address_text: Option<String>,
-        timestamp: OffsetDateTime,
+        timestamp: Option<OffsetDateTime>,
         text: String,
+        pdu: Option<Box<Pdu>>,
     },
     NewSmsMessage {
         storage: SmsMessageStorage,
         index: u16,
     },
+    PreferredStorage {
+        storage: SmsMessageStorage,
+        used: u16,
+        total: u16,
+    },
     ListCurrentCalls {
         index: u16,
         direction: CallDirection,
@@ -366,11 +584,98 @@ pub enum Response {
         number_type: Option<u16>,
         name: Option<String>,
     },
+    SendSmsMessage {
+        reference: u8,
+    },
+    StatusReport {
+        reference: u8,
+        recipient: Option<Address>,
+        timestamp: OffsetDateTime,
+        discharge_time: OffsetDateTime,
+        status: u8,
+    },
+    NewStatusReport {
+        storage: SmsMessageStorage,
+        index: u16,
+    },
+    UnstructuredData {
+        status: u8,
+        text: Option<String>,
+        coding: Option<u8>,
+    },
     CallReady,
     SmsReady,
     Ring,
     NoCarrier,
+    Busy,
+    NoAnswer,
+    NoDialtone,
+    Prompt,
+    /// Binary payload following length prefixed response, like `+HTTPREAD`.
+    Data(Vec<u8>),
     Empty,
+    /// Line not recognized by other parsers, `prefix` holds name of `+XXX: ...` information
+    /// response and `raw` its value, or whole line without prefix.
+    Unknown {
+        prefix: Option<String>,
+        raw: String,
+    },
+}
+
+impl Response {
+    /// Checks that response is unsolicited result code which is not a reply to command.
+    pub fn is_unsolicited(&self) -> bool {
+        matches!(
+            self,
+            Self::NewSmsMessage { .. }
+                | Self::StatusReport { .. }
+                | Self::NewStatusReport { .. }
+                | Self::UnstructuredData { .. }
+                | Self::CallReady
+                | Self::SmsReady
+                | Self::Ring
+                | Self::NoCarrier
+                | Self::Busy
+                | Self::NoAnswer
+                | Self::NoDialtone
+        )
+    }
+
+    /// Checks that response is final result code of `command`. Call result codes end voice
+    /// call command `ATD` when call fails before `OK`, or when `OK` follows connection with
+    /// `AT+COLP=1`, otherwise they are unsolicited.
+    pub fn is_final_during(&self, command: &str) -> bool {
+        match self {
+            Self::Ok | Self::Error(_) => true,
+            Self::NoCarrier | Self::Busy | Self::NoAnswer | Self::NoDialtone => {
+                command.to_ascii_uppercase().starts_with("ATD")
+            }
+            _ => false,
+        }
+    }
+
+    /// Checks that response is unsolicited while `command` waits for reply. Information
+    /// responses of other commands, like `+CPIN: READY` and `+CFUN: 1` sent after power on,
+    /// are unsolicited too.
+    pub fn is_unsolicited_during(&self, command: &str) -> bool {
+        let command = command.to_ascii_uppercase();
+        let queried = |prefix: &str| {
+            command
+                .strip_prefix("AT")
+                .is_some_and(|command| command.starts_with(prefix))
+        };
+
+        match self {
+            response if response.is_final_during(&command) => false,
+            Self::PinStatus { .. } => !queried("+CPIN"),
+            Self::Unknown {
+                prefix: Some(prefix),
+                ..
+            } => !queried(prefix),
+            Self::Unknown { prefix: None, raw } => UNSOLICITED_CODES.contains(&raw.trim()),
+            response => response.is_unsolicited(),
+        }
+    }
 }
 
 fn ucs2_to_uft8(text: &str) -> String {
@@ -383,24 +688,83 @@ fn ucs2_to_uft8(text: &str) -> String {
         chars.next().and_then(|ch| ch.to_digit(16)),
         chars.next().and_then(|ch| ch.to_digit(16)),
     ) {
-        if let Some(ch) = char::from_u32(a << 12 | b << 8 | c << 4 | d << 0) {
-            result.push(ch as char);
+        if let Some(ch) = char::from_u32(a << 12 | b << 8 | c << 4 | d) {
+            result.push(ch);
         }
     }
 
     result
 }
 
+pub fn utf8_to_ucs2(text: &str) -> String {
+    text.encode_utf16()
+        .map(|code| format!("{:04X}", code))
+        .collect()
+}
+
 fn parse_quoted_text<'a>() -> impl Parser<&'a str, Output = &'a str, Error = NomError<&'a str>> {
     alt((tag("\"\""), delimited(char('"'), is_not("\""), char('"'))))
 }
 
-fn parse_quoted_hex<'a>() -> impl Parser<&'a str, Output = String, Error = NomError<&'a str>> {
-    map(delimited(char('"'), hex_digit0, char('"')), ucs2_to_uft8)
+fn parse_quoted_string<'a>(
+    charset: CharacterSet,
+) -> impl Parser<&'a str, Output = String, Error = NomError<&'a str>> {
+    map(
+        delimited(char('"'), opt(is_not("\"")), char('"')),
+        move |text: Option<&str>| charset.decode(text.unwrap_or_default()),
+    )
 }
 
-fn parse_string_hex<'a>() -> impl Parser<&'a str, Output = String, Error = NomError<&'a str>> {
-    map(hex_digit0, ucs2_to_uft8)
+fn parse_quoted_raw<'a>() -> impl Parser<&'a str, Output = &'a str, Error = NomError<&'a str>> {
+    map(
+        delimited(char('"'), opt(is_not("\"")), char('"')),
+        Option::unwrap_or_default,
+    )
+}
+
+fn is_phone_number(text: &str) -> bool {
+    !text.is_empty()
+        && text
+            .chars()
+            .all(|ch| matches!(ch, '0'..='9' | '+' | '*' | '#'))
+}
+
+/// Creates address from `<oa>`/`<da>` and optional `<tooa>`/`<toda>` fields. Some modules send
+/// numbers and alphanumeric senders as is even when UCS2 character set selected, so decoded text
+/// is used only if it looks like phone number or raw text does not.
+fn create_address(charset: CharacterSet, text: &str, type_of_address: Option<u8>) -> Address {
+    let decoded = charset.decode(text);
+    let number = if is_phone_number(&decoded) || (!is_phone_number(text) && !decoded.is_empty()) {
+        decoded
+    } else {
+        text.into()
+    };
+
+    match type_of_address {
+        Some(type_of_address) => Address::with_type(&number, type_of_address),
+        None => Address::parse(&number),
+    }
+}
+
+/// Parses optional header fields shown with `AT+CSDH=1`, only type of address is kept.
+fn parse_header_fields<'a>() -> impl Parser<&'a str, Output = Option<u8>, Error = NomError<&'a str>>
+{
+    map(
+        (opt(preceded(char(','), u8)), opt(is_not("\r"))),
+        |(type_of_address, _)| type_of_address,
+    )
+}
+
+fn parse_string<'a>(
+    charset: CharacterSet,
+) -> impl Parser<&'a str, Output = String, Error = NomError<&'a str>> {
+    map(opt(is_not("\r")), move |text: Option<&str>| {
+        charset.decode(text.unwrap_or_default())
+    })
+}
+
+fn parse_pdu<'a>() -> impl Parser<&'a str, Output = Pdu, Error = NomError<&'a str>> {
+    map_res(hex_digit1, Pdu::from_hex)
 }
 
 fn parse_timestamp<'a>() -> impl Parser<&'a str, Output = OffsetDateTime, Error = NomError<&'a str>>
@@ -420,23 +784,29 @@ fn parse_timestamp<'a>() -> impl Parser<&'a str, Output = OffsetDateTime, Error
         	u8,
         	i8,
         	char('"')),
-        |(_, year,_, month,_, day,_, hour,_, minute,_, second, _, _)| -> Result<OffsetDateTime, Box<dyn Error>> {
+        |(_, year,_, month,_, day,_, hour,_, minute,_, second, quarters, _)| -> Result<OffsetDateTime, Box<dyn Error>> {
         	let date = Date::from_calendar_date(2000 + year as i32, Month::try_from(month)?, day)?;
         	let time = Time::from_hms(hour, minute, second)?;
+        	let offset = UtcOffset::from_whole_seconds(quarters as i32 * 15 * 60)?;
 
-            Ok(OffsetDateTime::new_utc(date, time))
+            Ok(PrimitiveDateTime::new(date, time).assume_offset(offset))
         },
     )
 }
 
 fn parse_operator_selection_mode<'a>(
 ) -> impl Parser<&'a str, Output = OperatorSelectionMode, Error = NomError<&'a str>> {
-    map(u8, OperatorSelectionMode::from)
+    map_res(u8, OperatorSelectionMode::try_from)
+}
+
+fn parse_operator_selection_format<'a>(
+) -> impl Parser<&'a str, Output = OperatorSelectionFormat, Error = NomError<&'a str>> {
+    map_res(u8, OperatorSelectionFormat::try_from)
 }
 
 fn parse_network_registration_mode<'a>(
 ) -> impl Parser<&'a str, Output = NetworkRegistrationMode, Error = NomError<&'a str>> {
-    map(u8, NetworkRegistrationMode::from)
+    map_res(u8, NetworkRegistrationMode::try_from)
 }
 
 fn parse_network_registration_status<'a>(
@@ -451,22 +821,27 @@ fn parse_phone_activity_status_status<'a>(
 
 fn parse_signal_quality_rssi<'a>(
 ) -> impl Parser<&'a str, Output = SignalQualityRssi, Error = NomError<&'a str>> {
-    map(u8, SignalQualityRssi::from)
+    map_res(u8, SignalQualityRssi::try_from)
 }
 
 fn parse_signal_quality_error_rate<'a>(
 ) -> impl Parser<&'a str, Output = SignalQualityErrorRate, Error = NomError<&'a str>> {
-    map(u8, SignalQualityErrorRate::from)
+    map_res(u8, SignalQualityErrorRate::try_from)
 }
 
 fn parse_battery_charge_status<'a>(
 ) -> impl Parser<&'a str, Output = BatteryChargeStatus, Error = NomError<&'a str>> {
-    map(u8, BatteryChargeStatus::from)
+    map_res(u8, BatteryChargeStatus::try_from)
 }
 
 fn parse_sms_messages_status<'a>(
 ) -> impl Parser<&'a str, Output = SmsMessageStatus, Error = NomError<&'a str>> {
-    map(parse_quoted_text(), SmsMessageStatus::from)
+    map_res(parse_quoted_text(), SmsMessageStatus::try_from)
+}
+
+fn parse_sms_message_status_code<'a>(
+) -> impl Parser<&'a str, Output = SmsMessageStatus, Error = NomError<&'a str>> {
+    map_res(u8, SmsMessageStatus::try_from)
 }
 
 fn parse_sms_message_storage<'a>(
@@ -476,20 +851,20 @@ fn parse_sms_message_storage<'a>(
 
 fn parse_call_direction<'a>(
 ) -> impl Parser<&'a str, Output = CallDirection, Error = NomError<&'a str>> {
-    map(u8, CallDirection::from)
+    map_res(u8, CallDirection::try_from)
 }
 
 fn parse_call_state<'a>() -> impl Parser<&'a str, Output = CallState, Error = NomError<&'a str>> {
-    map(u8, CallState::from)
+    map_res(u8, CallState::try_from)
 }
 
 fn parse_call_mode<'a>() -> impl Parser<&'a str, Output = CallMode, Error = NomError<&'a str>> {
-    map(u8, CallMode::from)
+    map_res(u8, CallMode::try_from)
 }
 
 fn parse_call_multiparty<'a>(
 ) -> impl Parser<&'a str, Output = CallMultiparty, Error = NomError<&'a str>> {
-    map(u8, CallMultiparty::from)
+    map_res(u8, CallMultiparty::try_from)
 }
 
 fn parse_operator_selection<'a>(
@@ -499,15 +874,24 @@ fn parse_operator_selection<'a>(
             tag("+COPS: "),
             parse_operator_selection_mode(),
             opt(map(
-                (char(','), u8, char(','), parse_quoted_text()),
+                (
+                    char(','),
+                    parse_operator_selection_format(),
+                    char(','),
+                    parse_quoted_text(),
+                ),
                 |(_, format, _, operator)| (format, operator),
             )),
             tag("\r\r"),
         ),
-        |(_, mode, args, _)| Response::OperatorSelection {
-            mode,
-            format: args.map(|(format, _)| format.into()),
-            operator: args.map(|(_, operator)| operator.into()),
+        |(_, mode, args, _)| {
+            let (format, operator) = args.unzip();
+
+            Response::OperatorSelection {
+                mode,
+                format,
+                operator: operator.map(String::from),
+            }
         },
     )
 }
@@ -552,6 +936,16 @@ fn parse_phone_activity_status<'a>(
     )
 }
 
+/// Parses `+CPIN` response, also sent as unsolicited code after power on.
+fn parse_pin_status<'a>() -> impl Parser<&'a str, Output = Response, Error = NomError<&'a str>> {
+    map(
+        (tag("+CPIN: "), is_not("\r"), alt((tag("\r\r"), tag("\r")))),
+        |(_, status, _)| Response::PinStatus {
+            status: PinStatus::from(status),
+        },
+    )
+}
+
 fn parse_signal_quality<'a>() -> impl Parser<&'a str, Output = Response, Error = NomError<&'a str>>
 {
     map(
@@ -587,6 +981,7 @@ fn parse_battery_charge<'a>() -> impl Parser<&'a str, Output = Response, Error =
 }
 
 fn parse_list_sms_messages<'a>(
+    charset: CharacterSet,
 ) -> impl Parser<&'a str, Output = Response, Error = NomError<&'a str>> {
     map(
         (
@@ -595,56 +990,151 @@ fn parse_list_sms_messages<'a>(
             char(','),
             parse_sms_messages_status(),
             char(','),
-            parse_quoted_hex(),
+            parse_quoted_raw(),
             char(','),
-            opt(parse_quoted_hex()),
+            opt(parse_quoted_string(charset)),
             char(','),
-            parse_timestamp(),
+            opt(parse_timestamp()),
+            parse_header_fields(),
             char('\r'),
-            parse_string_hex(),
+            parse_string(charset),
             tag("\r\r"),
         ),
-        |(_, index, _, status, _, address, _, address_text, _, timestamp, _, text, _)| {
+        move |(
+            _,
+            index,
+            _,
+            status,
+            _,
+            address,
+            _,
+            address_text,
+            _,
+            timestamp,
+            type_of_address,
+            _,
+            text,
+            _,
+        )| {
             Response::ListSmsMessage {
                 index,
                 status,
-                address: address.into(),
+                address: create_address(charset, address, type_of_address),
                 address_text,
                 timestamp,
                 text,
+                pdu: None,
             }
         },
     )
 }
 
 fn parse_read_sms_messages<'a>(
+    charset: CharacterSet,
 ) -> impl Parser<&'a str, Output = Response, Error = NomError<&'a str>> {
     map(
         (
             tag("+CMGR: "),
             parse_sms_messages_status(),
             char(','),
-            parse_quoted_hex(),
+            parse_quoted_raw(),
             char(','),
-            opt(parse_quoted_hex()),
+            opt(parse_quoted_string(charset)),
             char(','),
-            parse_timestamp(),
+            opt(parse_timestamp()),
+            parse_header_fields(),
             char('\r'),
-            parse_string_hex(),
+            parse_string(charset),
             tag("\r\r"),
         ),
-        |(_, status, _, address, _, address_text, _, timestamp, _, text, _)| {
+        move |(
+            _,
+            status,
+            _,
+            address,
+            _,
+            address_text,
+            _,
+            timestamp,
+            type_of_address,
+            _,
+            text,
+            _,
+        )| {
             Response::ReadSmsMessage {
                 status,
-                address: address.into(),
+                address: create_address(charset, address, type_of_address),
                 address_text,
                 timestamp,
                 text,
+                pdu: None,
             }
         },
     )
 }
 
+fn parse_list_sms_pdu<'a>(
+    charset: CharacterSet,
+) -> impl Parser<&'a str, Output = Response, Error = NomError<&'a str>> {
+    map(
+        (
+            tag("+CMGL: "),
+            u16,
+            char(','),
+            parse_sms_message_status_code(),
+            char(','),
+            opt(parse_quoted_string(charset)),
+            char(','),
+            u16,
+            char('\r'),
+            parse_pdu(),
+            tag("\r\r"),
+        ),
+        |(_, index, _, status, _, address_text, _, _, _, pdu, _)| Response::ListSmsMessage {
+            index,
+            status,
+            address: pdu.address().clone(),
+            address_text,
+            timestamp: pdu.timestamp(),
+            text: pdu.text(),
+            pdu: Some(Box::new(pdu)),
+        },
+    )
+}
+
+fn parse_read_sms_pdu<'a>(
+    charset: CharacterSet,
+) -> impl Parser<&'a str, Output = Response, Error = NomError<&'a str>> {
+    map(
+        (
+            tag("+CMGR: "),
+            parse_sms_message_status_code(),
+            char(','),
+            opt(parse_quoted_string(charset)),
+            char(','),
+            u16,
+            char('\r'),
+            parse_pdu(),
+            tag("\r\r"),
+        ),
+        |(_, status, _, address_text, _, _, _, pdu, _)| Response::ReadSmsMessage {
+            status,
+            address: pdu.address().clone(),
+            address_text,
+            timestamp: pdu.timestamp(),
+            text: pdu.text(),
+            pdu: Some(Box::new(pdu)),
+        },
+    )
+}
+
+fn parse_send_sms_message<'a>() -> impl Parser<&'a str, Output = Response, Error = NomError<&'a str>>
+{
+    map((tag("+CMGS: "), u8, tag("\r\r")), |(_, reference, _)| {
+        Response::SendSmsMessage { reference }
+    })
+}
+
 fn parse_new_sms_messages<'a>() -> impl Parser<&'a str, Output = Response, Error = NomError<&'a str>>
 {
     map(
@@ -659,6 +1149,123 @@ fn parse_new_sms_messages<'a>() -> impl Parser<&'a str, Output = Response, Error
     )
 }
 
+/// Parses `+CPMS` response, only storage used to read and delete messages returned.
+fn parse_preferred_storage<'a>(
+) -> impl Parser<&'a str, Output = Response, Error = NomError<&'a str>> {
+    map(
+        (
+            tag("+CPMS: "),
+            parse_sms_message_storage(),
+            char(','),
+            u16,
+            char(','),
+            u16,
+            opt(is_not("\r")),
+            tag("\r\r"),
+        ),
+        |(_, storage, _, used, _, total, _, _)| Response::PreferredStorage {
+            storage,
+            used,
+            total,
+        },
+    )
+}
+
+fn parse_status_report_text<'a>(
+    charset: CharacterSet,
+) -> impl Parser<&'a str, Output = Response, Error = NomError<&'a str>> {
+    map(
+        (
+            tag("+CDS: "),
+            u8,
+            char(','),
+            u8,
+            char(','),
+            opt(parse_quoted_raw()),
+            char(','),
+            opt(u8),
+            char(','),
+            parse_timestamp(),
+            char(','),
+            parse_timestamp(),
+            char(','),
+            u8,
+            char('\r'),
+        ),
+        move |(
+            _,
+            _,
+            _,
+            reference,
+            _,
+            recipient,
+            _,
+            type_of_address,
+            _,
+            timestamp,
+            _,
+            discharge_time,
+            _,
+            status,
+            _,
+        )| Response::StatusReport {
+            reference,
+            recipient: recipient.map(|text| create_address(charset, text, type_of_address)),
+            timestamp,
+            discharge_time,
+            status,
+        },
+    )
+}
+
+fn parse_status_report_pdu<'a>(
+) -> impl Parser<&'a str, Output = Response, Error = NomError<&'a str>> {
+    map_res(
+        (tag("+CDS: "), u16, char('\r'), parse_pdu(), char('\r')),
+        |(_, _, _, pdu, _)| match pdu {
+            Pdu::StatusReport(report) => Ok(Response::StatusReport {
+                reference: report.message_reference,
+                recipient: Some(report.recipient),
+                timestamp: report.timestamp,
+                discharge_time: report.discharge_time,
+                status: report.status,
+            }),
+            _ => Err(PduError::Malformed),
+        },
+    )
+}
+
+fn parse_new_status_report<'a>(
+) -> impl Parser<&'a str, Output = Response, Error = NomError<&'a str>> {
+    map(
+        (
+            tag("+CDSI: "),
+            parse_sms_message_storage(),
+            char(','),
+            u16,
+            char('\r'),
+        ),
+        |(_, storage, _, index, _)| Response::NewStatusReport { storage, index },
+    )
+}
+
+fn parse_sms<'a>(
+    charset: CharacterSet,
+) -> impl Parser<&'a str, Output = Response, Error = NomError<&'a str>> {
+    alt((
+        parse_list_sms_messages(charset),
+        parse_read_sms_messages(charset),
+        parse_list_sms_pdu(charset),
+        parse_read_sms_pdu(charset),
+        parse_new_sms_messages(),
+        parse_send_sms_message(),
+        parse_status_report_text(charset),
+        parse_status_report_pdu(),
+        parse_new_status_report(),
+        parse_preferred_storage(),
+    ))
+}
+
 fn parse_list_current_calls<'a>(
 ) -> impl Parser<&'a str, Output = Response, Error = NomError<&'a str>> {
     map(
@@ -711,16 +1318,12 @@ fn parse_error<'a>() -> impl Parser<&'a str, Output = Response, Error = NomError
 fn parse_error_code<'a>() -> impl Parser<&'a str, Output = Response, Error = NomError<&'a str>> {
     alt((
         map((tag("+CME ERROR: "), u16, char('\r')), |(_, code, _)| {
-            Response::Error(MobileEquipmentError::Code {
-                source: EquipmentErrorSource::Mobile,
+            Response::Error(MobileEquipmentError::Equipment(EquipmentError::from_code(
                 code,
-            })
+            )))
         }),
         map((tag("+CMS ERROR: "), u16, char('\r')), |(_, code, _)| {
-            Response::Error(MobileEquipmentError::Code {
-                source: EquipmentErrorSource::Service,
-                code,
-            })
+            Response::Error(MobileEquipmentError::Service(ServiceError::from_code(code)))
         }),
     ))
 }
@@ -729,20 +1332,18 @@ fn parse_error_message<'a>() -> impl Parser<&'a str, Output = Response, Error =
     alt((
         map(
             (tag("+CME ERROR: "), is_not("\r"), char('\r')),
-            |(_, message, _): (_, &str, _)| {
-                Response::Error(MobileEquipmentError::Message {
-                    source: EquipmentErrorSource::Mobile,
-                    message: message.into(),
-                })
+            |(_, message, _)| {
+                Response::Error(MobileEquipmentError::Equipment(
+                    EquipmentError::from_message(message),
+                ))
             },
         ),
         map(
             (tag("+CMS ERROR: "), is_not("\r"), char('\r')),
-            |(_, message, _): (_, &str, _)| {
-                Response::Error(MobileEquipmentError::Message {
-                    source: EquipmentErrorSource::Service,
-                    message: message.into(),
-                })
+            |(_, message, _)| {
+                Response::Error(MobileEquipmentError::Service(ServiceError::from_message(
+                    message,
+                )))
             },
         ),
     ))
@@ -764,11 +1365,75 @@ fn parse_no_carrier<'a>() -> impl Parser<&'a str, Output = Response, Error = Nom
     map(tag("NO CARRIER\r"), |_| Response::NoCarrier)
 }
 
+fn parse_busy<'a>() -> impl Parser<&'a str, Output = Response, Error = NomError<&'a str>> {
+    map(tag("BUSY\r"), |_| Response::Busy)
+}
+
+fn parse_no_answer<'a>() -> impl Parser<&'a str, Output = Response, Error = NomError<&'a str>> {
+    map(tag("NO ANSWER\r"), |_| Response::NoAnswer)
+}
+
+fn parse_no_dialtone<'a>() -> impl Parser<&'a str, Output = Response, Error = NomError<&'a str>> {
+    map(tag("NO DIALTONE\r"), |_| Response::NoDialtone)
+}
+
+/// Parses `+CUSD` reply to USSD request, text encoded with selected TE character set.
+fn parse_unstructured_data<'a>(
+    charset: CharacterSet,
+) -> impl Parser<&'a str, Output = Response, Error = NomError<&'a str>> {
+    map(
+        (
+            tag("+CUSD: "),
+            u8,
+            opt(preceded(char(','), parse_quoted_string(charset))),
+            opt(preceded(char(','), u8)),
+            char('\r'),
+        ),
+        |(_, status, text, coding, _)| Response::UnstructuredData {
+            status,
+            text,
+            coding,
+        },
+    )
+}
+
+fn parse_call<'a>() -> impl Parser<&'a str, Output = Response, Error = NomError<&'a str>> {
+    alt((
+        parse_ring(),
+        parse_no_carrier(),
+        parse_busy(),
+        parse_no_answer(),
+        parse_no_dialtone(),
+        parse_list_current_calls(),
+    ))
+}
+
 fn parse_empty<'a>() -> impl Parser<&'a str, Output = Response, Error = NomError<&'a str>> {
     map(char('\r'), |_| Response::Empty)
 }
 
-fn parser<'a>() -> impl Parser<&'a str, Output = Response, Error = NomError<&'a str>> {
+/// Parses unrecognized line, information responses followed by empty line preferred, so single
+/// line waits for next line before it is parsed.
+fn parse_unknown<'a>() -> impl Parser<&'a str, Output = Response, Error = NomError<&'a str>> {
+    map(
+        (
+            opt(terminated(
+                recognize((char('+'), alphanumeric1)),
+                (char(':'), space0),
+            )),
+            is_not("\r"),
+            alt((tag("\r\r"), tag("\r"))),
+        ),
+        |(prefix, raw, _): (Option<&str>, &str, _)| Response::Unknown {
+            prefix: prefix.map(String::from),
+            raw: raw.into(),
+        },
+    )
+}
+
+fn parser<'a>(
+    charset: CharacterSet,
+) -> impl Parser<&'a str, Output = Response, Error = NomError<&'a str>> {
     alt((
         parse_ok(),
         parse_error(),
@@ -776,18 +1441,17 @@ fn parser<'a>() -> impl Parser<&'a str, Output = Response, Error = NomError<&'a
         parse_error_message(),
         parse_call_ready(),
         parse_sms_ready(),
-        parse_ring(),
-        parse_no_carrier(),
         parse_operator_selection(),
         parse_network_registration(),
         parse_phone_activity_status(),
+        parse_pin_status(),
         parse_signal_quality(),
         parse_battery_charge(),
-        parse_list_sms_messages(),
-        parse_read_sms_messages(),
-        parse_new_sms_messages(),
-        parse_list_current_calls(),
+        parse_sms(charset),
+        parse_unstructured_data(charset),
+        parse_call(),
         parse_empty(),
+        parse_unknown(),
     ))
 }
 
@@ -798,8 +1462,8 @@ pub enum ParseResult {
     Error(String),
 }
 
-pub fn parse(data: &str) -> ParseResult {
-    match parser().parse(data) {
+pub fn parse(data: &str, charset: CharacterSet) -> ParseResult {
+    match parser(charset).parse(data) {
         Ok((tail, response)) => ParseResult::Success {
             response,
             tail: tail.into(),
@@ -808,3 +1472,51 @@ pub fn parse(data: &str) -> ParseResult {
         Err(error) => ParseResult::Error(error.to_string()),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::datetime;
+
+    fn timestamp(text: &str) -> OffsetDateTime {
+        let (tail, timestamp) = parse_timestamp().parse(text).unwrap();
+
+        assert_eq!(tail, "");
+
+        timestamp
+    }
+
+    #[test]
+    fn parses_timestamp_with_negative_offset() {
+        let result = timestamp(r#""23/12/31,23:30:00-08""#);
+
+        assert_eq!(result, datetime!(2023-12-31 23:30:00 -2));
+        assert_eq!(result.offset(), UtcOffset::from_hms(-2, 0, 0).unwrap());
+        assert_eq!(
+            result.to_offset(UtcOffset::UTC),
+            datetime!(2024-01-01 01:30:00 UTC)
+        );
+    }
+
+    #[test]
+    fn parses_timestamp_with_positive_offset() {
+        assert_eq!(
+            timestamp(r#""24/01/01,00:30:00+08""#).to_offset(UtcOffset::UTC),
+            datetime!(2023-12-31 22:30:00 UTC)
+        );
+        assert_eq!(
+            timestamp(r#""24/02/29,12:00:00-14""#),
+            datetime!(2024-02-29 12:00:00 -3:30)
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_timestamp() {
+        assert!(parse_timestamp()
+            .parse(r#""23/02/29,12:00:00+00""#)
+            .is_err());
+        assert!(parse_timestamp()
+            .parse(r#""23/13/01,12:00:00+00""#)
+            .is_err());
+    }
+}
@@ -0,0 +1,1147 @@
+use std::error::Error;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Result as FmtResult;
+
+use nom::bytes::complete::take;
+use nom::combinator::cond;
+use nom::combinator::map_res;
+use nom::number::complete::u8;
+use nom::IResult;
+use nom::Parser;
+use time::Date;
+use time::Month;
+use time::OffsetDateTime;
+use time::PrimitiveDateTime;
+use time::Time;
+use time::UtcOffset;
+
+use crate::address::Address;
+use crate::address::TypeOfNumber;
+use crate::gsm;
+use crate::gsm::GsmError;
+
+const MESSAGE_TYPE_DELIVER: u8 = 0x00;
+const MESSAGE_TYPE_SUBMIT: u8 = 0x01;
+const MESSAGE_TYPE_STATUS_REPORT: u8 = 0x02;
+
+const FLAG_MORE_MESSAGES: u8 = 0x04;
+const FLAG_REJECT_DUPLICATES: u8 = 0x04;
+const FLAG_STATUS_REPORT: u8 = 0x20;
+const FLAG_USER_DATA_HEADER: u8 = 0x40;
+const FLAG_REPLY_PATH: u8 = 0x80;
+
+const VALIDITY_NONE: u8 = 0x00;
+const VALIDITY_ENHANCED: u8 = 0x08;
+const VALIDITY_RELATIVE: u8 = 0x10;
+const VALIDITY_ABSOLUTE: u8 = 0x18;
+
+const MAX_USER_DATA_OCTETS: usize = 140;
+const MAX_USER_DATA_SEPTETS: usize = 160;
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub enum PduError {
+    InvalidHex,
+    Malformed,
+    UnsupportedMessageType(u8),
+    UnencodableCharacter(char),
+    UserDataTooLong(usize),
+    TooManyParts(usize),
+}
+
+impl From<GsmError> for PduError {
+    fn from(error: GsmError) -> Self {
+        match error {
+            GsmError::UnencodableCharacter(ch) => PduError::UnencodableCharacter(ch),
+        }
+    }
+}
+
+impl Error for PduError {}
+
+impl Display for PduError {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            Self::InvalidHex => write!(f, "Invalid hexadecimal PDU string"),
+            Self::Malformed => write!(f, "Malformed PDU"),
+            Self::UnsupportedMessageType(value) => {
+                write!(f, "Unsupported message type indicator: {}", value)
+            }
+            Self::UnencodableCharacter(ch) => {
+                write!(
+                    f,
+                    "Character {:?} can not be encoded with selected alphabet",
+                    ch
+                )
+            }
+            Self::UserDataTooLong(length) => write!(f, "User data too long: {}", length),
+            Self::TooManyParts(count) => {
+                write!(
+                    f,
+                    "Text requires {} message parts, at most 255 allowed",
+                    count
+                )
+            }
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum Alphabet {
+    Gsm7,
+    Data8,
+    Ucs2,
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum MessageClass {
+    Flash,
+    MobileEquipment,
+    Sim,
+    TerminalEquipment,
+}
+
+impl From<u8> for MessageClass {
+    fn from(value: u8) -> Self {
+        match value & 0x03 {
+            0 => Self::Flash,
+            1 => Self::MobileEquipment,
+            2 => Self::Sim,
+            _ => Self::TerminalEquipment,
+        }
+    }
+}
+
+impl From<MessageClass> for u8 {
+    fn from(value: MessageClass) -> Self {
+        match value {
+            MessageClass::Flash => 0,
+            MessageClass::MobileEquipment => 1,
+            MessageClass::Sim => 2,
+            MessageClass::TerminalEquipment => 3,
+        }
+    }
+}
+
+/// TP-DCS (data coding scheme) field, 3GPP TS 23.038 section 4.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct DataCoding {
+    pub alphabet: Alphabet,
+    pub class: Option<MessageClass>,
+    pub compressed: bool,
+}
+
+impl DataCoding {
+    pub fn new(alphabet: Alphabet) -> Self {
+        Self {
+            alphabet,
+            class: None,
+            compressed: false,
+        }
+    }
+
+    /// Selects GSM 7 bit alphabet when every character can be represented with it and UCS2
+    /// otherwise.
+    pub fn for_text(text: &str) -> Self {
+        if gsm::is_encodable(text) {
+            Self::new(Alphabet::Gsm7)
+        } else {
+            Self::new(Alphabet::Ucs2)
+        }
+    }
+}
+
+impl From<u8> for DataCoding {
+    fn from(value: u8) -> Self {
+        let alphabet = |bits: u8| match bits & 0x03 {
+            1 => Alphabet::Data8,
+            2 => Alphabet::Ucs2,
+            _ => Alphabet::Gsm7,
+        };
+
+        match value >> 4 {
+            0x00..=0x07 => Self {
+                alphabet: alphabet(value >> 2),
+                class: (value & 0x10 != 0).then(|| MessageClass::from(value)),
+                compressed: value & 0x20 != 0,
+            },
+            0x0C | 0x0D => Self::new(Alphabet::Gsm7),
+            0x0E => Self::new(Alphabet::Ucs2),
+            0x0F => Self {
+                alphabet: if value & 0x04 != 0 {
+                    Alphabet::Data8
+                } else {
+                    Alphabet::Gsm7
+                },
+                class: Some(MessageClass::from(value)),
+                compressed: false,
+            },
+            _ => Self::new(Alphabet::Gsm7),
+        }
+    }
+}
+
+impl From<DataCoding> for u8 {
+    fn from(value: DataCoding) -> Self {
+        let alphabet = match value.alphabet {
+            Alphabet::Gsm7 => 0x00,
+            Alphabet::Data8 => 0x04,
+            Alphabet::Ucs2 => 0x08,
+        };
+        let class = match value.class {
+            Some(class) => 0x10 | u8::from(class),
+            None => 0x00,
+        };
+        let compressed = if value.compressed { 0x20 } else { 0x00 };
+
+        compressed | class | alphabet
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct Concatenation {
+    pub reference: u16,
+    pub total: u8,
+    pub sequence: u8,
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub enum InformationElement {
+    Concatenation8(Concatenation),
+    Concatenation16(Concatenation),
+    ApplicationPort8 { destination: u8, source: u8 },
+    ApplicationPort16 { destination: u16, source: u16 },
+    Other { identifier: u8, data: Vec<u8> },
+}
+
+impl InformationElement {
+    fn decode(identifier: u8, data: &[u8]) -> Self {
+        match (identifier, data) {
+            (0x00, &[reference, total, sequence]) => Self::Concatenation8(Concatenation {
+                reference: reference as u16,
+                total,
+                sequence,
+            }),
+            (0x08, &[high, low, total, sequence]) => Self::Concatenation16(Concatenation {
+                reference: u16::from_be_bytes([high, low]),
+                total,
+                sequence,
+            }),
+            (0x04, &[destination, source]) => Self::ApplicationPort8 {
+                destination,
+                source,
+            },
+            (0x05, &[destination_high, destination_low, source_high, source_low]) => {
+                Self::ApplicationPort16 {
+                    destination: u16::from_be_bytes([destination_high, destination_low]),
+                    source: u16::from_be_bytes([source_high, source_low]),
+                }
+            }
+            _ => Self::Other {
+                identifier,
+                data: data.into(),
+            },
+        }
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let (identifier, data) = match self {
+            Self::Concatenation8(concatenation) => (
+                0x00,
+                vec![
+                    concatenation.reference as u8,
+                    concatenation.total,
+                    concatenation.sequence,
+                ],
+            ),
+            Self::Concatenation16(concatenation) => {
+                let [high, low] = concatenation.reference.to_be_bytes();
+
+                (
+                    0x08,
+                    vec![high, low, concatenation.total, concatenation.sequence],
+                )
+            }
+            Self::ApplicationPort8 {
+                destination,
+                source,
+            } => (0x04, vec![*destination, *source]),
+            Self::ApplicationPort16 {
+                destination,
+                source,
+            } => {
+                let [destination_high, destination_low] = destination.to_be_bytes();
+                let [source_high, source_low] = source.to_be_bytes();
+
+                (
+                    0x05,
+                    vec![destination_high, destination_low, source_high, source_low],
+                )
+            }
+            Self::Other { identifier, data } => (*identifier, data.clone()),
+        };
+        let mut result = vec![identifier, data.len() as u8];
+        result.extend(data);
+
+        result
+    }
+}
+
+/// User data header (TP-UDH), 3GPP TS 23.040 section 9.2.3.24.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Default)]
+pub struct UserDataHeader {
+    pub elements: Vec<InformationElement>,
+}
+
+impl UserDataHeader {
+    pub fn concatenation(&self) -> Option<Concatenation> {
+        self.elements.iter().find_map(|element| match element {
+            InformationElement::Concatenation8(concatenation)
+            | InformationElement::Concatenation16(concatenation) => Some(*concatenation),
+            _ => None,
+        })
+    }
+
+    fn decode(mut data: &[u8]) -> Result<Self, PduError> {
+        let mut elements = Vec::new();
+
+        while let [identifier, length, tail @ ..] = data {
+            let length = *length as usize;
+            let value = tail.get(..length).ok_or(PduError::Malformed)?;
+
+            elements.push(InformationElement::decode(*identifier, value));
+            data = &tail[length..];
+        }
+
+        match data {
+            [] => Ok(Self { elements }),
+            _ => Err(PduError::Malformed),
+        }
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let elements: Vec<u8> = self
+            .elements
+            .iter()
+            .flat_map(InformationElement::encode)
+            .collect();
+        let mut result = vec![elements.len() as u8];
+        result.extend(elements);
+
+        result
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub enum Content {
+    Text(String),
+    Binary(Vec<u8>),
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct UserData {
+    pub header: Option<UserDataHeader>,
+    pub content: Content,
+}
+
+impl UserData {
+    pub fn text(text: &str) -> Self {
+        Self {
+            header: None,
+            content: Content::Text(text.into()),
+        }
+    }
+
+    /// Returns message text, binary content is represented as hexadecimal string.
+    pub fn to_text(&self) -> String {
+        match &self.content {
+            Content::Text(text) => text.clone(),
+            Content::Binary(data) => encode_hex(data),
+        }
+    }
+
+    fn decode(
+        data: &[u8],
+        length: usize,
+        has_header: bool,
+        coding: DataCoding,
+    ) -> Result<Self, PduError> {
+        let (header, header_length) = match (has_header, data.first()) {
+            (true, Some(&header_length)) => {
+                let header_length = header_length as usize + 1;
+                let header = data.get(1..header_length).ok_or(PduError::Malformed)?;
+
+                (Some(UserDataHeader::decode(header)?), header_length)
+            }
+            (true, None) => return Err(PduError::Malformed),
+            (false, _) => (None, 0),
+        };
+        let content = match coding.alphabet {
+            Alphabet::Gsm7 if !coding.compressed => {
+                let skip = (header_length * 8).div_ceil(7);
+                let count = length.checked_sub(skip).ok_or(PduError::Malformed)?;
+                let septets = gsm::unpack(data, skip, count).ok_or(PduError::Malformed)?;
+
+                Content::Text(gsm::decode(&septets))
+            }
+            Alphabet::Ucs2 if !coding.compressed => {
+                let octets = data.get(header_length..length).ok_or(PduError::Malformed)?;
+                let units: Vec<u16> = octets
+                    .chunks(2)
+                    .map(|chunk| match *chunk {
+                        [high, low] => u16::from_be_bytes([high, low]),
+                        [high] => u16::from_be_bytes([high, 0]),
+                        _ => 0,
+                    })
+                    .collect();
+
+                Content::Text(String::from_utf16_lossy(&units))
+            }
+            _ => Content::Binary(
+                data.get(header_length..length)
+                    .ok_or(PduError::Malformed)?
+                    .into(),
+            ),
+        };
+
+        Ok(Self { header, content })
+    }
+
+    fn encode(&self, coding: DataCoding) -> Result<(u8, Vec<u8>), PduError> {
+        let header = self
+            .header
+            .as_ref()
+            .map(UserDataHeader::encode)
+            .unwrap_or_default();
+
+        match (coding.alphabet, &self.content) {
+            (Alphabet::Gsm7, content) if !coding.compressed => {
+                let septets = match content {
+                    Content::Text(text) => gsm::encode(text)?,
+                    Content::Binary(data) => data.iter().map(|septet| septet & 0x7F).collect(),
+                };
+                let skip = (header.len() * 8).div_ceil(7);
+                let length = skip + septets.len();
+
+                if length > MAX_USER_DATA_SEPTETS {
+                    return Err(PduError::UserDataTooLong(length));
+                }
+
+                let mut data = gsm::pack(&septets, skip);
+                data[..header.len()].copy_from_slice(&header);
+
+                Ok((length as u8, data))
+            }
+            (alphabet, content) => {
+                let mut data = header;
+
+                match content {
+                    Content::Text(text) if alphabet == Alphabet::Ucs2 => {
+                        data.extend(text.encode_utf16().flat_map(|unit| unit.to_be_bytes()))
+                    }
+                    Content::Text(text) => data.extend(text.as_bytes()),
+                    Content::Binary(binary) => data.extend(binary),
+                }
+
+                if data.len() > MAX_USER_DATA_OCTETS {
+                    return Err(PduError::UserDataTooLong(data.len()));
+                }
+
+                Ok((data.len() as u8, data))
+            }
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub enum ValidityPeriod {
+    Relative(u8),
+    Absolute(OffsetDateTime),
+    Enhanced([u8; 7]),
+}
+
+/// SMS-DELIVER, message received from service center.
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct SmsDeliver {
+    pub smsc: Option<Address>,
+    pub more_messages: bool,
+    pub reply_path: bool,
+    pub status_report_indication: bool,
+    pub originator: Address,
+    pub protocol_identifier: u8,
+    pub data_coding: DataCoding,
+    pub timestamp: OffsetDateTime,
+    pub user_data: UserData,
+}
+
+/// SMS-SUBMIT, message sent to service center.
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct SmsSubmit {
+    pub smsc: Option<Address>,
+    pub reject_duplicates: bool,
+    pub reply_path: bool,
+    pub status_report_request: bool,
+    pub message_reference: u8,
+    pub destination: Address,
+    pub protocol_identifier: u8,
+    pub data_coding: DataCoding,
+    pub validity_period: Option<ValidityPeriod>,
+    pub user_data: UserData,
+}
+
+impl SmsSubmit {
+    pub fn new(destination: Address, data_coding: DataCoding, user_data: UserData) -> Self {
+        Self {
+            smsc: None,
+            reject_duplicates: false,
+            reply_path: false,
+            status_report_request: false,
+            message_reference: 0,
+            destination,
+            protocol_identifier: 0,
+            data_coding,
+            validity_period: None,
+            user_data,
+        }
+    }
+}
+
+/// SMS-STATUS-REPORT, delivery status of previously submitted message.
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct SmsStatusReport {
+    pub smsc: Option<Address>,
+    pub more_messages: bool,
+    pub status_report_qualifier: bool,
+    pub message_reference: u8,
+    pub recipient: Address,
+    pub timestamp: OffsetDateTime,
+    pub discharge_time: OffsetDateTime,
+    pub status: u8,
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub enum Pdu {
+    Deliver(SmsDeliver),
+    Submit(SmsSubmit),
+    StatusReport(SmsStatusReport),
+}
+
+impl Pdu {
+    pub fn from_hex(text: &str) -> Result<Self, PduError> {
+        Self::decode(&decode_hex(text)?)
+    }
+
+    pub fn decode(data: &[u8]) -> Result<Self, PduError> {
+        let (input, smsc) = parse_smsc(data).map_err(|_| PduError::Malformed)?;
+        let first_octet = *input.first().ok_or(PduError::Malformed)?;
+        let (_, pdu) = match first_octet & 0x03 {
+            MESSAGE_TYPE_DELIVER => parse_deliver(input, smsc),
+            MESSAGE_TYPE_SUBMIT => parse_submit(input, smsc),
+            MESSAGE_TYPE_STATUS_REPORT => parse_status_report(input, smsc),
+            value => return Err(PduError::UnsupportedMessageType(value)),
+        }
+        .map_err(|error| match error {
+            nom::Err::Error(error) | nom::Err::Failure(error) => error.0,
+            nom::Err::Incomplete(_) => PduError::Malformed,
+        })?;
+
+        Ok(pdu)
+    }
+
+    pub fn to_hex(&self) -> Result<String, PduError> {
+        Ok(encode_hex(&self.encode()?))
+    }
+
+    pub fn encode(&self) -> Result<Vec<u8>, PduError> {
+        let mut result = Vec::new();
+
+        match self {
+            Self::Deliver(deliver) => {
+                let (length, data) = deliver.user_data.encode(deliver.data_coding)?;
+
+                result.extend(encode_smsc(deliver.smsc.as_ref())?);
+                result.push(
+                    MESSAGE_TYPE_DELIVER
+                        | flag(!deliver.more_messages, FLAG_MORE_MESSAGES)
+                        | flag(deliver.status_report_indication, FLAG_STATUS_REPORT)
+                        | flag(deliver.user_data.header.is_some(), FLAG_USER_DATA_HEADER)
+                        | flag(deliver.reply_path, FLAG_REPLY_PATH),
+                );
+                result.extend(encode_address(&deliver.originator)?);
+                result.push(deliver.protocol_identifier);
+                result.push(deliver.data_coding.into());
+                result.extend(encode_timestamp(deliver.timestamp));
+                result.push(length);
+                result.extend(data);
+            }
+            Self::Submit(submit) => {
+                let (length, data) = submit.user_data.encode(submit.data_coding)?;
+                let validity_format = match submit.validity_period {
+                    None => VALIDITY_NONE,
+                    Some(ValidityPeriod::Relative(_)) => VALIDITY_RELATIVE,
+                    Some(ValidityPeriod::Absolute(_)) => VALIDITY_ABSOLUTE,
+                    Some(ValidityPeriod::Enhanced(_)) => VALIDITY_ENHANCED,
+                };
+
+                result.extend(encode_smsc(submit.smsc.as_ref())?);
+                result.push(
+                    MESSAGE_TYPE_SUBMIT
+                        | flag(submit.reject_duplicates, FLAG_REJECT_DUPLICATES)
+                        | validity_format
+                        | flag(submit.status_report_request, FLAG_STATUS_REPORT)
+                        | flag(submit.user_data.header.is_some(), FLAG_USER_DATA_HEADER)
+                        | flag(submit.reply_path, FLAG_REPLY_PATH),
+                );
+                result.push(submit.message_reference);
+                result.extend(encode_address(&submit.destination)?);
+                result.push(submit.protocol_identifier);
+                result.push(submit.data_coding.into());
+
+                match submit.validity_period {
+                    None => {}
+                    Some(ValidityPeriod::Relative(value)) => result.push(value),
+                    Some(ValidityPeriod::Absolute(timestamp)) => {
+                        result.extend(encode_timestamp(timestamp))
+                    }
+                    Some(ValidityPeriod::Enhanced(value)) => result.extend(value),
+                }
+
+                result.push(length);
+                result.extend(data);
+            }
+            Self::StatusReport(report) => {
+                result.extend(encode_smsc(report.smsc.as_ref())?);
+                result.push(
+                    MESSAGE_TYPE_STATUS_REPORT
+                        | flag(!report.more_messages, FLAG_MORE_MESSAGES)
+                        | flag(report.status_report_qualifier, FLAG_STATUS_REPORT),
+                );
+                result.push(report.message_reference);
+                result.extend(encode_address(&report.recipient)?);
+                result.extend(encode_timestamp(report.timestamp));
+                result.extend(encode_timestamp(report.discharge_time));
+                result.push(report.status);
+            }
+        }
+
+        Ok(result)
+    }
+
+    pub fn smsc(&self) -> Option<&Address> {
+        match self {
+            Self::Deliver(deliver) => deliver.smsc.as_ref(),
+            Self::Submit(submit) => submit.smsc.as_ref(),
+            Self::StatusReport(report) => report.smsc.as_ref(),
+        }
+    }
+
+    /// Returns originator address for received messages and destination address otherwise.
+    pub fn address(&self) -> &Address {
+        match self {
+            Self::Deliver(deliver) => &deliver.originator,
+            Self::Submit(submit) => &submit.destination,
+            Self::StatusReport(report) => &report.recipient,
+        }
+    }
+
+    /// Returns service center time stamp, for status reports it is message discharge time.
+    pub fn timestamp(&self) -> Option<OffsetDateTime> {
+        match self {
+            Self::Deliver(deliver) => Some(deliver.timestamp),
+            Self::Submit(_) => None,
+            Self::StatusReport(report) => Some(report.discharge_time),
+        }
+    }
+
+    pub fn user_data(&self) -> Option<&UserData> {
+        match self {
+            Self::Deliver(deliver) => Some(&deliver.user_data),
+            Self::Submit(submit) => Some(&submit.user_data),
+            Self::StatusReport(_) => None,
+        }
+    }
+
+    pub fn text(&self) -> String {
+        self.user_data().map(UserData::to_text).unwrap_or_default()
+    }
+
+    pub fn concatenation(&self) -> Option<Concatenation> {
+        self.user_data()
+            .and_then(|user_data| user_data.header.as_ref())
+            .and_then(UserDataHeader::concatenation)
+    }
+}
+
+/// Returns text length in units of the alphabet: septets for GSM 7 bit, UTF-16 code units for
+/// UCS2 and octets for 8 bit data.
+pub fn text_length(text: &str, alphabet: Alphabet) -> usize {
+    match alphabet {
+        Alphabet::Gsm7 => gsm::septet_count(text).unwrap_or_else(|_| text.chars().count()),
+        Alphabet::Ucs2 => text.encode_utf16().count(),
+        Alphabet::Data8 => text.len(),
+    }
+}
+
+pub fn decode_hex(text: &str) -> Result<Vec<u8>, PduError> {
+    if !text.len().is_multiple_of(2) {
+        return Err(PduError::InvalidHex);
+    }
+
+    (0..text.len())
+        .step_by(2)
+        .map(|index| {
+            text.get(index..index + 2)
+                .and_then(|digits| u8::from_str_radix(digits, 16).ok())
+                .ok_or(PduError::InvalidHex)
+        })
+        .collect()
+}
+
+pub fn encode_hex(data: &[u8]) -> String {
+    data.iter().map(|value| format!("{:02X}", value)).collect()
+}
+
+type PduResult<'a, T> = IResult<&'a [u8], T, PduParseError>;
+
+/// Wrapper allowing to keep `PduError` inside nom errors.
+#[derive(Debug)]
+struct PduParseError(PduError);
+
+impl<'a> nom::error::ParseError<&'a [u8]> for PduParseError {
+    fn from_error_kind(_: &'a [u8], _: nom::error::ErrorKind) -> Self {
+        Self(PduError::Malformed)
+    }
+
+    fn append(_: &'a [u8], _: nom::error::ErrorKind, other: Self) -> Self {
+        other
+    }
+}
+
+impl<'a> nom::error::FromExternalError<&'a [u8], PduError> for PduParseError {
+    fn from_external_error(_: &'a [u8], _: nom::error::ErrorKind, error: PduError) -> Self {
+        Self(error)
+    }
+}
+
+fn flag(value: bool, mask: u8) -> u8 {
+    if value {
+        mask
+    } else {
+        0
+    }
+}
+
+fn parse_smsc(input: &[u8]) -> PduResult<'_, Option<Address>> {
+    let (input, length) = u8(input)?;
+
+    if length == 0 {
+        return Ok((input, None));
+    }
+
+    let (input, type_of_address) = u8(input)?;
+    let (input, digits) = take(length as usize - 1)(input)?;
+
+    Ok((
+        input,
+        Some(Address::with_type(
+            &semi_octets_to_text(digits),
+            type_of_address,
+        )),
+    ))
+}
+
+fn parse_address(input: &[u8]) -> PduResult<'_, Address> {
+    let (input, length) = u8(input)?;
+    let (input, type_of_address) = u8(input)?;
+    let (input, data) = take((length as usize).div_ceil(2))(input)?;
+    let number = match TypeOfNumber::from(type_of_address >> 4) {
+        TypeOfNumber::Alphanumeric => {
+            let count = length as usize * 4 / 7;
+
+            gsm::decode(&gsm::unpack(data, 0, count).unwrap_or_default())
+        }
+        _ => semi_octets_to_text(data),
+    };
+
+    Ok((input, Address::with_type(&number, type_of_address)))
+}
+
+fn parse_timestamp(input: &[u8]) -> PduResult<'_, OffsetDateTime> {
+    map_res(take(7usize), decode_timestamp).parse(input)
+}
+
+fn parse_user_data(input: &[u8], has_header: bool, coding: DataCoding) -> PduResult<'_, UserData> {
+    let (input, length) = u8(input)?;
+    let octets = match coding.alphabet {
+        Alphabet::Gsm7 if !coding.compressed => (length as usize * 7).div_ceil(8),
+        _ => length as usize,
+    };
+    let (input, data) = take(octets)(input)?;
+    let user_data = UserData::decode(data, length as usize, has_header, coding)
+        .map_err(|error| nom::Err::Failure(PduParseError(error)))?;
+
+    Ok((input, user_data))
+}
+
+fn parse_deliver(input: &[u8], smsc: Option<Address>) -> PduResult<'_, Pdu> {
+    let (input, first_octet) = u8(input)?;
+    let (input, originator) = parse_address(input)?;
+    let (input, protocol_identifier) = u8(input)?;
+    let (input, data_coding) = u8(input)?;
+    let (input, timestamp) = parse_timestamp(input)?;
+    let data_coding = DataCoding::from(data_coding);
+    let (input, user_data) =
+        parse_user_data(input, first_octet & FLAG_USER_DATA_HEADER != 0, data_coding)?;
+
+    Ok((
+        input,
+        Pdu::Deliver(SmsDeliver {
+            smsc,
+            more_messages: first_octet & FLAG_MORE_MESSAGES == 0,
+            reply_path: first_octet & FLAG_REPLY_PATH != 0,
+            status_report_indication: first_octet & FLAG_STATUS_REPORT != 0,
+            originator,
+            protocol_identifier,
+            data_coding,
+            timestamp,
+            user_data,
+        }),
+    ))
+}
+
+fn parse_submit(input: &[u8], smsc: Option<Address>) -> PduResult<'_, Pdu> {
+    let (input, first_octet) = u8(input)?;
+    let (input, message_reference) = u8(input)?;
+    let (input, destination) = parse_address(input)?;
+    let (input, protocol_identifier) = u8(input)?;
+    let (input, data_coding) = u8(input)?;
+    let validity_format = first_octet & VALIDITY_ABSOLUTE;
+    let (input, relative) = cond(validity_format == VALIDITY_RELATIVE, u8).parse(input)?;
+    let (input, absolute) =
+        cond(validity_format == VALIDITY_ABSOLUTE, parse_timestamp).parse(input)?;
+    let (input, enhanced) =
+        cond(validity_format == VALIDITY_ENHANCED, take(7usize)).parse(input)?;
+    let validity_period = match (relative, absolute, enhanced) {
+        (Some(value), _, _) => Some(ValidityPeriod::Relative(value)),
+        (_, Some(timestamp), _) => Some(ValidityPeriod::Absolute(timestamp)),
+        (_, _, Some(value)) => {
+            let mut enhanced = [0; 7];
+            enhanced.copy_from_slice(value);
+
+            Some(ValidityPeriod::Enhanced(enhanced))
+        }
+        _ => None,
+    };
+    let data_coding = DataCoding::from(data_coding);
+    let (input, user_data) =
+        parse_user_data(input, first_octet & FLAG_USER_DATA_HEADER != 0, data_coding)?;
+
+    Ok((
+        input,
+        Pdu::Submit(SmsSubmit {
+            smsc,
+            reject_duplicates: first_octet & FLAG_REJECT_DUPLICATES != 0,
+            reply_path: first_octet & FLAG_REPLY_PATH != 0,
+            status_report_request: first_octet & FLAG_STATUS_REPORT != 0,
+            message_reference,
+            destination,
+            protocol_identifier,
+            data_coding,
+            validity_period,
+            user_data,
+        }),
+    ))
+}
+
+fn parse_status_report(input: &[u8], smsc: Option<Address>) -> PduResult<'_, Pdu> {
+    let (input, first_octet) = u8(input)?;
+    let (input, message_reference) = u8(input)?;
+    let (input, recipient) = parse_address(input)?;
+    let (input, timestamp) = parse_timestamp(input)?;
+    let (input, discharge_time) = parse_timestamp(input)?;
+    let (input, status) = u8(input)?;
+
+    Ok((
+        input,
+        Pdu::StatusReport(SmsStatusReport {
+            smsc,
+            more_messages: first_octet & FLAG_MORE_MESSAGES == 0,
+            status_report_qualifier: first_octet & FLAG_STATUS_REPORT != 0,
+            message_reference,
+            recipient,
+            timestamp,
+            discharge_time,
+            status,
+        }),
+    ))
+}
+
+fn encode_smsc(smsc: Option<&Address>) -> Result<Vec<u8>, PduError> {
+    match smsc {
+        Some(address) => {
+            let digits = text_to_semi_octets(address.number())?;
+            let mut result = vec![digits.len() as u8 + 1, address.type_of_address()];
+            result.extend(digits);
+
+            Ok(result)
+        }
+        None => Ok(vec![0]),
+    }
+}
+
+fn encode_address(address: &Address) -> Result<Vec<u8>, PduError> {
+    let (length, data) = if address.is_alphanumeric() {
+        let septets = gsm::encode(address.number())?;
+
+        ((septets.len() * 7).div_ceil(4), gsm::pack(&septets, 0))
+    } else {
+        (
+            address.number().len(),
+            text_to_semi_octets(address.number())?,
+        )
+    };
+    let mut result = vec![length as u8, address.type_of_address()];
+    result.extend(data);
+
+    Ok(result)
+}
+
+fn decode_timestamp(data: &[u8]) -> Result<OffsetDateTime, PduError> {
+    let value = |index: usize| (data[index] & 0x0F) * 10 + (data[index] >> 4);
+    let quarters = ((data[6] & 0x07) * 10 + (data[6] >> 4)) as i32;
+    let quarters = if data[6] & 0x08 != 0 {
+        -quarters
+    } else {
+        quarters
+    };
+    let month = Month::try_from(value(1)).map_err(|_| PduError::Malformed)?;
+    let date = Date::from_calendar_date(2000 + value(0) as i32, month, value(2))
+        .map_err(|_| PduError::Malformed)?;
+    let time = Time::from_hms(value(3), value(4), value(5)).map_err(|_| PduError::Malformed)?;
+    let offset =
+        UtcOffset::from_whole_seconds(quarters * 15 * 60).map_err(|_| PduError::Malformed)?;
+
+    Ok(PrimitiveDateTime::new(date, time).assume_offset(offset))
+}
+
+fn encode_timestamp(timestamp: OffsetDateTime) -> [u8; 7] {
+    let value = |value: u8| (value % 10) << 4 | (value / 10);
+    let quarters = timestamp.offset().whole_seconds() / (15 * 60);
+    let sign = if quarters < 0 { 0x08 } else { 0x00 };
+
+    [
+        value(timestamp.year().rem_euclid(100) as u8),
+        value(timestamp.month().into()),
+        value(timestamp.day()),
+        value(timestamp.hour()),
+        value(timestamp.minute()),
+        value(timestamp.second()),
+        value(quarters.unsigned_abs() as u8) | sign,
+    ]
+}
+
+fn semi_octets_to_text(data: &[u8]) -> String {
+    data.iter()
+        .flat_map(|value| [value & 0x0F, value >> 4])
+        .filter_map(|digit| match digit {
+            0..=9 => Some((b'0' + digit) as char),
+            0x0A => Some('*'),
+            0x0B => Some('#'),
+            0x0C => Some('a'),
+            0x0D => Some('b'),
+            0x0E => Some('c'),
+            _ => None,
+        })
+        .collect()
+}
+
+fn text_to_semi_octets(text: &str) -> Result<Vec<u8>, PduError> {
+    let digits = text
+        .chars()
+        .map(|ch| match ch {
+            '0'..='9' => Ok(ch as u8 - b'0'),
+            '*' => Ok(0x0A),
+            '#' => Ok(0x0B),
+            'a' => Ok(0x0C),
+            'b' => Ok(0x0D),
+            'c' => Ok(0x0E),
+            _ => Err(PduError::UnencodableCharacter(ch)),
+        })
+        .collect::<Result<Vec<u8>, _>>()?;
+
+    Ok(digits
+        .chunks(2)
+        .map(|pair| match *pair {
+            [low, high] => high << 4 | low,
+            [low] => 0xF0 | low,
+            _ => 0xFF,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::datetime;
+
+    #[test]
+    fn decodes_timestamp_with_negative_offset() {
+        // Bit 3 of time zone octet is sign, -08 quarters of an hour.
+        let data = [0x32, 0x21, 0x13, 0x32, 0x03, 0x00, 0x88];
+        let result = decode_timestamp(&data).unwrap();
+
+        assert_eq!(result, datetime!(2023-12-31 23:30:00 -2));
+        assert_eq!(
+            result.to_offset(UtcOffset::UTC),
+            datetime!(2024-01-01 01:30:00 UTC)
+        );
+        assert_eq!(encode_timestamp(result), data);
+    }
+
+    #[test]
+    fn decodes_timestamp_with_two_digit_offset() {
+        let negative = [0x42, 0x10, 0x10, 0x00, 0x03, 0x00, 0x69];
+        let positive = [0x42, 0x10, 0x10, 0x00, 0x03, 0x00, 0x61];
+
+        assert_eq!(
+            decode_timestamp(&negative).unwrap(),
+            datetime!(2024-01-01 00:30:00 -4)
+        );
+        assert_eq!(
+            decode_timestamp(&positive)
+                .unwrap()
+                .to_offset(UtcOffset::UTC),
+            datetime!(2023-12-31 20:30:00 UTC)
+        );
+        assert_eq!(
+            encode_timestamp(datetime!(2024-01-01 00:30:00 -4)),
+            negative
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_timestamp() {
+        assert!(decode_timestamp(&[0x32, 0x20, 0x92, 0x21, 0x00, 0x00, 0x00]).is_err());
+        assert!(decode_timestamp(&[0x32, 0x31, 0x10, 0x21, 0x00, 0x00, 0x00]).is_err());
+    }
+
+    /// Decodes PDU, checks that encoding gives the same PDU back.
+    fn round_trip(hex: &str) -> Pdu {
+        let pdu = Pdu::from_hex(hex).unwrap();
+
+        assert_eq!(pdu.to_hex().unwrap(), hex);
+
+        pdu
+    }
+
+    fn deliver(hex: &str) -> SmsDeliver {
+        match round_trip(hex) {
+            Pdu::Deliver(deliver) => deliver,
+            pdu => panic!("Unexpected PDU {:?}", pdu),
+        }
+    }
+
+    #[test]
+    fn decodes_deliver() {
+        let deliver =
+            deliver("07917283010010F5040BC87238880900F10000993092516195800AE8329BFD4697D9EC37");
+
+        assert_eq!(deliver.smsc.unwrap().to_string(), "+27381000015");
+        assert!(!deliver.more_messages);
+        assert_eq!(deliver.originator.to_string(), "27838890001");
+        assert_eq!(deliver.originator.type_of_address(), 0xC8);
+        assert_eq!(deliver.data_coding, DataCoding::new(Alphabet::Gsm7));
+        // Time zone octet 0x80 is +08 quarters of an hour.
+        assert_eq!(
+            deliver.timestamp.offset(),
+            UtcOffset::from_hms(2, 0, 0).unwrap()
+        );
+        assert_eq!(
+            (deliver.timestamp.month(), deliver.timestamp.day()),
+            (Month::March, 29)
+        );
+        assert_eq!(
+            deliver.timestamp.time(),
+            Time::from_hms(15, 16, 59).unwrap()
+        );
+        assert_eq!(deliver.user_data, UserData::text("hellohello"));
+    }
+
+    #[test]
+    fn encodes_submit() {
+        let hex = "0011000B916407281553F80000AA0AE8329BFD4697D9EC37";
+        let mut submit = SmsSubmit::new(
+            Address::parse("+46708251358"),
+            DataCoding::new(Alphabet::Gsm7),
+            UserData::text("hellohello"),
+        );
+
+        submit.validity_period = Some(ValidityPeriod::Relative(0xAA));
+
+        assert_eq!(Pdu::Submit(submit.clone()).to_hex().unwrap(), hex);
+        assert_eq!(round_trip(hex), Pdu::Submit(submit));
+    }
+
+    #[test]
+    fn decodes_status_report() {
+        let pdu = round_trip("00062A0B916407281553F8322113320300003221133213000000");
+
+        let Pdu::StatusReport(report) = pdu else {
+            panic!("Unexpected PDU {:?}", pdu);
+        };
+
+        assert_eq!(report.message_reference, 0x2A);
+        assert!(!report.more_messages);
+        assert_eq!(report.recipient.to_string(), "+46708251358");
+        assert_eq!(report.timestamp, datetime!(2023-12-31 23:30:00 UTC));
+        assert_eq!(report.discharge_time, datetime!(2023-12-31 23:31:00 UTC));
+        assert_eq!(report.status, 0);
+    }
+
+    #[test]
+    fn decodes_concatenation_header() {
+        let deliver = deliver(concat!(
+            "00440B916407281553F8000032211332030000",
+            "0C050003CC0201D06536FB0D"
+        ));
+
+        assert_eq!(
+            deliver.user_data.header.as_ref().unwrap().concatenation(),
+            Some(Concatenation {
+                reference: 0xCC,
+                total: 2,
+                sequence: 1
+            })
+        );
+        assert_eq!(deliver.user_data.content, Content::Text("hello".into()));
+    }
+
+    #[test]
+    fn decodes_alphanumeric_originator() {
+        let deliver = deliver("00040DD049B7F93D6D4E0100003221133203000005E8329BFD06");
+
+        assert!(deliver.originator.is_alphanumeric());
+        assert_eq!(deliver.originator.to_string(), "InfoSMS");
+        assert_eq!(deliver.user_data, UserData::text("hello"));
+    }
+
+    #[test]
+    fn decodes_ucs2_text() {
+        let deliver = deliver(concat!(
+            "00040B916407281553F8000832211332030000",
+            "0C041F04400438043204350442"
+        ));
+
+        assert_eq!(deliver.data_coding, DataCoding::new(Alphabet::Ucs2));
+        assert_eq!(deliver.user_data, UserData::text("Привет"));
+    }
+
+    #[test]
+    fn decodes_8_bit_data() {
+        let deliver = deliver("00040B916407281553F800043221133203000003010203");
+
+        assert_eq!(deliver.data_coding, DataCoding::new(Alphabet::Data8));
+        assert_eq!(deliver.user_data.content, Content::Binary(vec![1, 2, 3]));
+        assert_eq!(deliver.user_data.to_text(), "010203");
+    }
+}
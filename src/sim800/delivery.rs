@@ -0,0 +1,97 @@
+use std::collections::HashMap;
+
+use crate::parser::Response;
+use crate::pdu::Pdu;
+
+/// Delivery state of sent message.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum DeliveryState {
+    Pending,
+    Delivered,
+    Failed(u8),
+}
+
+impl DeliveryState {
+    /// Converts TP-Status value, 3GPP TS 23.040 section 9.2.3.15. Temporary errors while service
+    /// center still trying to deliver message keep message pending.
+    pub fn from_status(status: u8) -> Self {
+        match status {
+            0x00..=0x1F => Self::Delivered,
+            0x20..=0x3F => Self::Pending,
+            _ => Self::Failed(status),
+        }
+    }
+
+    pub fn is_pending(&self) -> bool {
+        *self == Self::Pending
+    }
+}
+
+/// Correlates delivery status reports with references of sent messages.
+#[derive(Debug, Default)]
+pub struct DeliveryTracker {
+    messages: HashMap<u8, DeliveryState>,
+}
+
+impl DeliveryTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts tracking message with given reference, previous state is replaced because
+    /// references wrap around after 255 messages.
+    pub fn register(&mut self, reference: u8) {
+        self.messages.insert(reference, DeliveryState::Pending);
+    }
+
+    /// Updates state of tracked message from TP-Status value. Returns new state or `None` if
+    /// message with this reference is not tracked.
+    pub fn update(&mut self, reference: u8, status: u8) -> Option<DeliveryState> {
+        let state = self.messages.get_mut(&reference)?;
+
+        *state = DeliveryState::from_status(status);
+
+        Some(*state)
+    }
+
+    /// Updates state from `+CDS` response or status report read from message storage.
+    pub fn update_response(&mut self, response: &Response) -> Option<DeliveryState> {
+        match response {
+            Response::StatusReport {
+                reference, status, ..
+            } => self.update(*reference, *status),
+            Response::ListSmsMessage { pdu: Some(pdu), .. }
+            | Response::ReadSmsMessage { pdu: Some(pdu), .. } => match pdu.as_ref() {
+                Pdu::StatusReport(report) => self.update(report.message_reference, report.status),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    pub fn state(&self, reference: u8) -> Option<DeliveryState> {
+        self.messages.get(&reference).copied()
+    }
+
+    /// Returns references of messages waiting for status report.
+    pub fn pending(&self) -> Vec<u8> {
+        let mut result: Vec<u8> = self
+            .messages
+            .iter()
+            .filter(|(_, state)| state.is_pending())
+            .map(|(&reference, _)| reference)
+            .collect();
+
+        result.sort_unstable();
+        result
+    }
+
+    pub fn has_pending(&self) -> bool {
+        self.messages.values().any(DeliveryState::is_pending)
+    }
+
+    /// Stops tracking message, returns last known state.
+    pub fn remove(&mut self, reference: u8) -> Option<DeliveryState> {
+        self.messages.remove(&reference)
+    }
+}
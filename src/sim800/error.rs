@@ -7,7 +7,12 @@ use std::io::Error as IoError;
 use std::sync::mpsc::RecvError;
 use std::sync::mpsc::SendError;
 
+use crate::gsm::GsmError;
+use crate::parser::MobileEquipmentError;
 use crate::parser::Response;
+use crate::pdu::PduError;
+
+use super::Command;
 
 #[derive(Debug)]
 pub enum Sim800Error {
@@ -15,6 +20,12 @@ pub enum Sim800Error {
     IoError(IoError),
     ResponseError(SendError<Response>),
     RecvError(RecvError),
+    CommandError(SendError<Command>),
+    PduError(PduError),
+    GsmError(GsmError),
+    Timeout(String),
+    MissingReply(String),
+    Modem(MobileEquipmentError),
 }
 
 impl From<SerialError> for Sim800Error {
@@ -41,6 +52,24 @@ impl From<RecvError> for Sim800Error {
     }
 }
 
+impl From<SendError<Command>> for Sim800Error {
+    fn from(error: SendError<Command>) -> Self {
+        Sim800Error::CommandError(error)
+    }
+}
+
+impl From<PduError> for Sim800Error {
+    fn from(error: PduError) -> Self {
+        Sim800Error::PduError(error)
+    }
+}
+
+impl From<GsmError> for Sim800Error {
+    fn from(error: GsmError) -> Self {
+        Sim800Error::GsmError(error)
+    }
+}
+
 impl Error for Sim800Error {}
 
 impl Display for Sim800Error {
@@ -50,6 +79,12 @@ impl Display for Sim800Error {
             Self::IoError(error) => write!(f, "{}", error),
             Self::ResponseError(error) => write!(f, "{}", error),
             Self::RecvError(error) => write!(f, "{}", error),
+            Self::CommandError(error) => write!(f, "{}", error),
+            Self::PduError(error) => write!(f, "{}", error),
+            Self::GsmError(error) => write!(f, "{}", error),
+            Self::Timeout(command) => write!(f, "No response to command {}", command),
+            Self::MissingReply(command) => write!(f, "No result in reply to command {}", command),
+            Self::Modem(error) => write!(f, "{}", error),
         }
     }
 }
@@ -0,0 +1,283 @@
+use std::collections::VecDeque;
+
+/// Prompt for message text after `AT+CMGS`, sent without line terminator.
+const PROMPT: &[u8] = b"> ";
+
+/// Responses followed by binary payload, payload length is first number after prefix.
+const DATA_PREFIXES: [&str; 3] = ["+HTTPREAD: ", "+FTPGET: 2,", "+CIPRXGET: 2,"];
+
+/// Part of module output separated by framer.
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub enum Frame {
+    /// Line terminated with `\r\n`, `\r` or `\n`, without terminator. Command echo with echo
+    /// enabled (`ATE1`) is terminated with single `\r`.
+    Line(String),
+    /// Prompt `> ` for message text, expected only with `expect_prompt`.
+    Prompt,
+    /// Binary payload following length prefixed response.
+    Data(Vec<u8>),
+}
+
+/// Splits bytes received from module into lines, command echoes, prompts and binary payloads.
+/// Bytes are fed in chunks of any size, incomplete frames wait for more bytes.
+#[derive(Debug, Default)]
+pub struct Framer {
+    buffer: VecDeque<u8>,
+    data_length: Option<usize>,
+    prompt: bool,
+    /// Set if bytes after last line break already framed, like prompt.
+    mid_line: bool,
+}
+
+impl Framer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends received bytes.
+    pub fn feed(&mut self, data: &[u8]) {
+        self.buffer.extend(data);
+    }
+
+    /// Treats next `length` bytes as binary payload. Payloads of known responses like
+    /// `+HTTPREAD` are detected automatically.
+    pub fn expect_data(&mut self, length: usize) {
+        self.data_length = Some(length);
+    }
+
+    /// Treats `> ` following line break as prompt while `expected`, like after `AT+CMGS`.
+    /// Otherwise `> ` starts normal line, like quoted text in message.
+    pub fn expect_prompt(&mut self, expected: bool) {
+        self.prompt = expected;
+    }
+
+    /// Returns next complete frame or `None` if more bytes required.
+    pub fn next_frame(&mut self) -> Option<Frame> {
+        if let Some(length) = self.data_length {
+            if self.buffer.len() < length {
+                return None;
+            }
+
+            self.data_length = None;
+
+            return Some(Frame::Data(self.buffer.drain(..length).collect()));
+        }
+
+        // Line feed of command echo follows reply because module echoes bytes as received.
+        while self.buffer.front() == Some(&b'\n') {
+            self.buffer.pop_front();
+        }
+
+        // Module waits for message text after prompt, so nothing follows it.
+        if self.prompt && !self.mid_line && self.buffer.iter().eq(PROMPT.iter()) {
+            self.buffer.clear();
+            self.mid_line = true;
+
+            return Some(Frame::Prompt);
+        }
+
+        let end = self
+            .buffer
+            .iter()
+            .position(|&byte| byte == b'\r' || byte == b'\n')?;
+        let skip = match (self.buffer[end], self.buffer.get(end + 1)) {
+            (b'\r', Some(b'\n')) => 2,
+            // Line feed may follow in next bytes.
+            (b'\r', None) => return None,
+            _ => 1,
+        };
+        let line: Vec<_> = self.buffer.drain(..end).collect();
+        let text = decode_line(&line);
+
+        self.buffer.drain(..skip);
+        self.mid_line = false;
+
+        self.data_length = data_length(&text);
+
+        Some(Frame::Line(text))
+    }
+}
+
+/// Decodes line as UTF-8, lines with other bytes decoded byte per character like GSM 7 bit
+/// alphabet text.
+fn decode_line(line: &[u8]) -> String {
+    match std::str::from_utf8(line) {
+        Ok(text) => text.into(),
+        Err(_) => line.iter().map(|&byte| byte as char).collect(),
+    }
+}
+
+/// Returns length of binary payload following response line.
+fn data_length(line: &str) -> Option<usize> {
+    let arguments = DATA_PREFIXES
+        .iter()
+        .find_map(|prefix| line.strip_prefix(prefix))?;
+    let length = arguments.split(',').next()?;
+
+    length.trim().parse().ok().filter(|&length| length > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frames(framer: &mut Framer) -> Vec<Frame> {
+        std::iter::from_fn(|| framer.next_frame()).collect()
+    }
+
+    fn line(text: &str) -> Frame {
+        Frame::Line(text.into())
+    }
+
+    #[test]
+    fn splits_lines() {
+        let mut framer = Framer::new();
+
+        framer.feed(b"\r\n+CSQ: 20,0\r\n\r\nOK\r\n");
+
+        assert_eq!(
+            frames(&mut framer),
+            [line(""), line("+CSQ: 20,0"), line(""), line("OK")]
+        );
+    }
+
+    #[test]
+    fn waits_for_line_feed_after_carriage_return() {
+        let mut framer = Framer::new();
+
+        framer.feed(b"\r\nOK\r");
+
+        assert_eq!(frames(&mut framer), [line("")]);
+
+        framer.feed(b"\n");
+
+        assert_eq!(frames(&mut framer), [line("OK")]);
+    }
+
+    #[test]
+    fn splits_echo_and_reply() {
+        let mut framer = Framer::new();
+
+        framer.feed(b"AT+CSQ\r\n\r\n+CSQ: 20,0\r\n\r\nOK\r\n");
+
+        assert_eq!(
+            frames(&mut framer),
+            [
+                line("AT+CSQ"),
+                line(""),
+                line("+CSQ: 20,0"),
+                line(""),
+                line("OK")
+            ]
+        );
+    }
+
+    #[test]
+    fn splits_lines_terminated_with_carriage_return() {
+        let mut framer = Framer::new();
+
+        framer.feed(b"AT\r\r\nOK\r\nline1\rline2\r\n");
+
+        assert_eq!(
+            frames(&mut framer),
+            [
+                line("AT"),
+                line(""),
+                line("OK"),
+                line("line1"),
+                line("line2")
+            ]
+        );
+    }
+
+    #[test]
+    fn detects_expected_prompt() {
+        let mut framer = Framer::new();
+
+        framer.expect_prompt(true);
+        framer.feed(b"AT+CMGS=20\r\r\n> ");
+
+        assert_eq!(
+            frames(&mut framer),
+            [line("AT+CMGS=20"), line(""), Frame::Prompt]
+        );
+
+        framer.feed(b"0011000B\x1a\r\n+CMGS: 1\r\n\r\nOK\r\n");
+
+        assert_eq!(
+            frames(&mut framer),
+            [line("0011000B\x1a"), line("+CMGS: 1"), line(""), line("OK")]
+        );
+    }
+
+    #[test]
+    fn ignores_unexpected_prompt() {
+        let mut framer = Framer::new();
+
+        framer.feed(b"+CMGL: 1,\"REC READ\",\"+123\",,\"23/12/31,23:30:00+08\"\r\n> quoted\r\n");
+
+        assert_eq!(
+            frames(&mut framer),
+            [
+                line("+CMGL: 1,\"REC READ\",\"+123\",,\"23/12/31,23:30:00+08\""),
+                line("> quoted")
+            ]
+        );
+    }
+
+    #[test]
+    fn ignores_quoted_line_while_prompt_expected() {
+        let mut framer = Framer::new();
+
+        framer.expect_prompt(true);
+        framer.feed(b"\r\n> quoted\r\n");
+
+        assert_eq!(frames(&mut framer), [line(""), line("> quoted")]);
+    }
+
+    #[test]
+    fn reads_payload_split_across_feeds() {
+        let mut framer = Framer::new();
+
+        framer.feed(b"\r\n+HTTPREAD: 14\r\n01234");
+
+        assert_eq!(frames(&mut framer), [line(""), line("+HTTPREAD: 14")]);
+
+        framer.feed(b"\r\n\r\n");
+
+        assert_eq!(frames(&mut framer), []);
+
+        framer.feed(b"56789\r\nOK\r\n");
+
+        assert_eq!(
+            frames(&mut framer),
+            [
+                Frame::Data(b"01234\r\n\r\n56789".to_vec()),
+                line(""),
+                line("OK")
+            ]
+        );
+    }
+
+    #[test]
+    fn reads_expected_payload() {
+        let mut framer = Framer::new();
+
+        framer.expect_data(3);
+        framer.feed(b"\r\n\xff");
+
+        assert_eq!(frames(&mut framer), [Frame::Data(b"\r\n\xff".to_vec())]);
+    }
+
+    #[test]
+    fn decodes_non_utf8_bytes_per_character() {
+        let mut framer = Framer::new();
+
+        framer.feed(b"caf\xe9 \x00\r\nna\xc3\xafve\r\n");
+
+        assert_eq!(
+            frames(&mut framer),
+            [line("caf\u{e9} \0"), line("na\u{ef}ve")]
+        );
+    }
+}
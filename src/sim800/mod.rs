@@ -1,81 +1,318 @@
+mod delivery;
 mod error;
+mod framing;
+mod readiness;
 
-use error::Sim800Error;
+pub use delivery::DeliveryState;
+pub use delivery::DeliveryTracker;
+pub use error::Sim800Error;
+pub use framing::Frame;
+pub use framing::Framer;
 use log::info;
 use log::warn;
-use serialport::SerialPort;
+pub use readiness::Readiness;
+use std::io::ErrorKind;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
 use std::sync::mpsc;
 use std::sync::mpsc::Receiver;
-use std::sync::mpsc::SendError;
+use std::sync::mpsc::RecvError;
+use std::sync::mpsc::RecvTimeoutError;
 use std::sync::mpsc::Sender;
+use std::sync::Arc;
+use std::sync::Mutex;
 use std::thread::Builder;
 use std::thread::JoinHandle;
+use std::time::Duration;
+use std::time::Instant;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
 
+use crate::address::Address;
+use crate::concat::segment_count;
+use crate::concat::split_submit;
+use crate::error_code::EquipmentError;
 use crate::parser::parse;
+use crate::parser::CharacterSet;
+use crate::parser::MobileEquipmentError;
 use crate::parser::ParseResult;
+use crate::parser::PinStatus;
 use crate::parser::Response;
+use crate::pdu::encode_hex;
+use crate::pdu::Pdu;
+use crate::pdu::PduError;
+use crate::transport::Transport;
+
+const CTRL_Z: u8 = 0x1A;
+
+const ESC: u8 = 0x1B;
+
+const READ_BUFFER_SIZE: usize = 256;
+
+/// Commands answered with `>` prompt for message text.
+const PROMPT_COMMANDS: [&str; 2] = ["AT+CMGS", "AT+CMSS"];
+
+/// SMS-SUBMIT first octet with relative validity period.
+const SUBMIT_FIRST_OCTET: u8 = 0x11;
+
+/// Status report request flag of SMS-SUBMIT first octet.
+const STATUS_REPORT_REQUEST: u8 = 0x20;
+
+/// Number of `AT` commands sent to synchronize autobaud.
+const SYNC_ATTEMPTS: u32 = 10;
+
+/// Response timeout of `AT` commands while autobaud synchronizes.
+const SYNC_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Interval to repeat readiness queries while module starts.
+const READY_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Default command response timeout.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Maximum response time in seconds for commands with long execution time, from SIM800 AT
+/// commands manual.
+const COMMAND_TIMEOUTS: [(&str, u64); 8] = [
+    ("AT+COPS=?", 120),
+    ("AT+CMGS", 60),
+    ("AT+CMSS", 60),
+    ("AT+CMGDA", 25),
+    ("AT+CMGD", 25),
+    ("AT+CMGL", 20),
+    ("AT+CUSD", 20),
+    ("ATD", 20),
+];
+
+#[derive(Debug)]
+pub enum Command {
+    Line(String),
+    Data(String),
+    Cancel,
+}
+
+/// Text received from module with its parsing result.
+#[derive(Debug, Clone)]
+pub enum Received {
+    Parsed { text: String, response: Response },
+    Unparsed { text: String, error: String },
+}
 
 #[derive(Debug)]
 pub struct Sim800 {
-    port: Box<dyn SerialPort>,
-    command_sender: Sender<String>,
+    command_sender: Sender<Command>,
     response_receiver: Receiver<Response>,
     command_write: JoinHandle<Result<(), Sim800Error>>,
     response_read: JoinHandle<Result<(), Sim800Error>>,
+    concatenation_reference: u8,
+    character_set: Arc<Mutex<CharacterSet>>,
+    stop: Arc<AtomicBool>,
+    subscribers: Arc<Mutex<Vec<Sender<Response>>>>,
+    monitors: Arc<Mutex<Vec<Sender<Received>>>>,
+    unsolicited_receiver: Receiver<Response>,
+    timeout: Duration,
+    status_report: bool,
+    pdu_mode: bool,
+    delivery: DeliveryTracker,
 }
 
 impl Sim800 {
-    pub fn new(port: Box<dyn SerialPort>) -> Result<Self, Sim800Error> {
-        let (command_sender, command_receiver) = mpsc::channel::<String>();
+    pub fn new(transport: Box<dyn Transport>) -> Result<Self, Sim800Error> {
+        let (command_sender, command_receiver) = mpsc::channel::<Command>();
         let (response_sender, response_receiver) = mpsc::channel();
-        let mut other = port.try_clone().map_err(Sim800Error::from)?;
+        let (mut reader, mut writer) = transport.split()?;
+        let pending_echo = Arc::new(Mutex::new(None));
+        let echo = pending_echo.clone();
+        // Last written command, data sent after prompt belongs to it.
+        let pending_command = Arc::new(Mutex::new(String::new()));
+        let pending = pending_command.clone();
         let command_write = Builder::new().spawn(move || {
             for command in command_receiver {
-                info!(">> {}", command);
-
-                other
-                    .write_all(command.as_bytes())
-                    .map_err(Sim800Error::from)?;
-                other
-                    .write_all("\r\n".as_bytes())
-                    .map_err(Sim800Error::from)?;
-                other.flush().map_err(Sim800Error::from)?;
+                if let (Ok(mut echo), Command::Line(text) | Command::Data(text)) =
+                    (pending_echo.lock(), &command)
+                {
+                    *echo = Some(text.clone());
+                }
+
+                if let (Ok(mut pending), Command::Line(line)) = (pending_command.lock(), &command) {
+                    *pending = line.to_ascii_uppercase();
+                }
+
+                match command {
+                    Command::Line(line) => {
+                        info!(">> {}", line);
+
+                        writer
+                            .write_all(line.as_bytes())
+                            .map_err(Sim800Error::from)?;
+                        writer
+                            .write_all("\r\n".as_bytes())
+                            .map_err(Sim800Error::from)?;
+                    }
+                    Command::Data(data) => {
+                        info!(">> {}", data);
+
+                        writer
+                            .write_all(data.as_bytes())
+                            .map_err(Sim800Error::from)?;
+                        writer.write_all(&[CTRL_Z]).map_err(Sim800Error::from)?;
+                    }
+                    Command::Cancel => {
+                        info!(">> <ESC>");
+
+                        writer.write_all(&[ESC]).map_err(Sim800Error::from)?;
+                    }
+                }
+
+                writer.flush().map_err(Sim800Error::from)?;
             }
 
             Ok(())
         })?;
-        let mut other = port.try_clone().map_err(Sim800Error::from)?;
+        let character_set = Arc::new(Mutex::new(CharacterSet::default()));
+        let charset = character_set.clone();
+        let stop = Arc::new(AtomicBool::new(false));
+        let stopped = stop.clone();
+        let (unsolicited_sender, unsolicited_receiver) = mpsc::channel();
+        let subscribers = Arc::new(Mutex::new(vec![unsolicited_sender]));
+        let listeners = subscribers.clone();
+        let monitors = Arc::new(Mutex::new(Vec::new()));
+        let observers = monitors.clone();
+        let notify = move |received: Received| {
+            if let Ok(mut observers) = observers.lock() {
+                observers
+                    .retain(|observer: &Sender<Received>| observer.send(received.clone()).is_ok());
+            }
+        };
         let response_read = Builder::new().spawn(move || {
-            let mut buffer: [u8; 1] = [0; 1];
-            let mut line = String::new();
+            let mut buffer = [0; READ_BUFFER_SIZE];
+            let mut framer = Framer::new();
             let mut text = String::new();
+            // Echo of last written command or data, stripped once when received.
+            let echoed = |line: &str| match echo.lock() {
+                Ok(mut echo) if echo.as_deref() == Some(line.trim_end_matches(CTRL_Z as char)) => {
+                    *echo = None;
 
-            loop {
-                match other.read(&mut buffer)? {
-                    0 => break,
-                    _ if buffer[0] == b'\n' => {
-                        if !line.ends_with("\r\r") {
-                            text.push_str(&line);
+                    true
+                }
+                _ => false,
+            };
+            let command = || {
+                pending
+                    .lock()
+                    .map(|command| command.clone())
+                    .unwrap_or_default()
+            };
+            let dispatch = |text: &mut String| -> Result<(), Sim800Error> {
+                let charset = charset.lock().map(|charset| *charset).unwrap_or_default();
+
+                // Tail may hold complete responses following unrecognized line.
+                while !text.is_empty() {
+                    match parse(text, charset) {
+                        ParseResult::Success { response, tail } => {
+                            notify(Received::Parsed {
+                                text: text[..text.len() - tail.len()].into(),
+                                response: response.clone(),
+                            });
 
-                            match parse(&text) {
-                                ParseResult::Success { response, tail } => {
-                                    response_sender.send(response)?;
+                            let command = command();
 
-                                    text.clear();
-                                    text.push_str(&tail);
+                            // Codes following final result are unsolicited.
+                            if response.is_final_during(&command) {
+                                if let Ok(mut pending) = pending.lock() {
+                                    pending.clear();
                                 }
-                                ParseResult::Incomplete => {}
-                                ParseResult::Error(error) => {
-                                    warn!("<< Error: {}, Text: {}", error, text);
+                            }
 
-                                    text.clear();
+                            if response.is_unsolicited_during(&command) {
+                                info!("<< Unsolicited: {:?}", response);
+
+                                if let Ok(mut listeners) = listeners.lock() {
+                                    listeners
+                                        .retain(|listener| listener.send(response.clone()).is_ok());
                                 }
+                            } else {
+                                response_sender.send(response)?;
                             }
+
+                            *text = tail;
+                        }
+                        ParseResult::Incomplete => break,
+                        ParseResult::Error(error) => {
+                            warn!("<< Error: {}, Text: {}", error, text);
+
+                            notify(Received::Unparsed {
+                                text: text.clone(),
+                                error,
+                            });
+
+                            text.clear();
+                        }
+                    }
+                }
+
+                Ok(())
+            };
+
+            loop {
+                let count = match reader.read(&mut buffer) {
+                    Err(error)
+                        if error.kind() == ErrorKind::TimedOut
+                            || error.kind() == ErrorKind::WouldBlock =>
+                    {
+                        if stopped.load(Ordering::Relaxed) {
+                            break;
+                        }
+
+                        continue;
+                    }
+                    result => result?,
+                };
+
+                if count == 0 {
+                    break;
+                }
+
+                framer.feed(&buffer[..count]);
+
+                let command = command();
+
+                framer.expect_prompt(
+                    PROMPT_COMMANDS
+                        .iter()
+                        .any(|prefix| command.starts_with(prefix)),
+                );
+
+                while let Some(frame) = framer.next_frame() {
+                    match frame {
+                        Frame::Line(line) if echoed(&line) => info!("<< Echo: {}", line),
+                        Frame::Line(line) => {
+                            text.push_str(&line);
+                            text.push('\r');
+
+                            dispatch(&mut text)?;
+                        }
+                        Frame::Prompt => {
+                            notify(Received::Parsed {
+                                text: "> ".into(),
+                                response: Response::Prompt,
+                            });
+                            response_sender.send(Response::Prompt)?;
                         }
+                        Frame::Data(data) => {
+                            // Header line preceding payload has no empty line after it.
+                            if !text.is_empty() {
+                                text.push('\r');
 
-                        line.clear();
+                                dispatch(&mut text)?;
+                            }
+
+                            notify(Received::Parsed {
+                                text: String::from_utf8_lossy(&data).into(),
+                                response: Response::Data(data.clone()),
+                            });
+                            response_sender.send(Response::Data(data))?;
+                        }
                     }
-                    _ => line.push(buffer[0] as char),
                 }
             }
 
@@ -83,67 +320,460 @@ impl Sim800 {
         })?;
 
         Ok(Self {
-            port,
             command_sender,
             response_receiver,
             command_write,
             response_read,
+            concatenation_reference: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|duration| duration.as_secs() as u8)
+                .unwrap_or_default(),
+            character_set,
+            stop,
+            subscribers,
+            monitors,
+            unsolicited_receiver,
+            timeout: DEFAULT_TIMEOUT,
+            status_report: false,
+            pdu_mode: true,
+            delivery: DeliveryTracker::new(),
         })
     }
 
-    pub fn send(&mut self, command: &str) -> Result<(), SendError<String>> {
-        self.command_sender.send(command.into())?;
+    pub fn character_set(&self) -> CharacterSet {
+        self.character_set
+            .lock()
+            .map(|charset| *charset)
+            .unwrap_or_default()
+    }
 
-        for response in &self.response_receiver {
-            info!("<< {:?}", response);
+    /// Selects TE character set (`AT+CSCS`) used to encode and decode text mode strings.
+    pub fn set_character_set(&mut self, charset: CharacterSet) -> Result<(), Sim800Error> {
+        self.send(&format!(r#"AT+CSCS="{}""#, charset))?;
 
-            match response {
-                Response::Ok | Response::Error(_) => break,
-                _ => {}
+        if let Ok(mut character_set) = self.character_set.lock() {
+            *character_set = charset;
+        }
+
+        Ok(())
+    }
+
+    /// Returns message format selected with `set_pdu_mode`, PDU mode is module default.
+    pub fn pdu_mode(&self) -> bool {
+        self.pdu_mode
+    }
+
+    /// Selects PDU (`AT+CMGF=0`) or text (`AT+CMGF=1`) message format.
+    pub fn set_pdu_mode(&mut self, enabled: bool) -> Result<(), Sim800Error> {
+        self.send(if enabled { "AT+CMGF=0" } else { "AT+CMGF=1" })?;
+        self.pdu_mode = enabled;
+
+        Ok(())
+    }
+
+    /// Sends `AT` until module answers, autobaud detects baud rate from these commands. Returns
+    /// number of sent commands.
+    fn synchronize(&mut self) -> Result<u32, Sim800Error> {
+        let timeout = self.timeout;
+        let mut result = Err(Sim800Error::Timeout("AT".into()));
+
+        self.timeout = SYNC_TIMEOUT;
+
+        for attempt in 1..=SYNC_ATTEMPTS {
+            match self.send("AT") {
+                Ok(()) => {
+                    result = Ok(attempt);
+
+                    break;
+                }
+                Err(Sim800Error::Timeout(_) | Sim800Error::Modem(_)) => {}
+                Err(error) => {
+                    result = Err(error);
+
+                    break;
+                }
+            }
+        }
+
+        self.timeout = timeout;
+
+        result
+    }
+
+    /// Synchronizes autobaud, enables verbose error messages, fixes baud rate with `AT+IPR` if
+    /// given and waits until SIM card,
+    /// calls and SMS are ready or `ready_timeout` expires. `Call Ready` and `SMS Ready` codes
+    /// are sent only once after power on, so readiness also queried with `AT+CCALR?` and
+    /// `AT+CPMS?`. Waiting stops early if SIM card requires password.
+    pub fn initialize(
+        &mut self,
+        baud_rate: Option<u32>,
+        ready_timeout: Duration,
+    ) -> Result<Readiness, Sim800Error> {
+        let codes = self.subscribe();
+        let mut result = Readiness::new(self.synchronize()?);
+
+        self.send("AT+CMEE=2")?;
+
+        if let Some(baud_rate) = baud_rate {
+            self.send(&format!("AT+IPR={}", baud_rate))?;
+        }
+
+        let deadline = Instant::now() + ready_timeout;
+        let mut call_query = true;
+
+        loop {
+            for code in codes.try_iter() {
+                result.update_response(&code);
+            }
+
+            if result.pin != Some(PinStatus::Ready) {
+                result.pin = match self.send_one("AT+CPIN?") {
+                    Ok(Some(Response::PinStatus { status })) => Some(status),
+                    Ok(_) => None,
+                    Err(Sim800Error::Modem(MobileEquipmentError::Equipment(
+                        EquipmentError::SimNotInserted,
+                    ))) => Some(PinStatus::NotInserted),
+                    Err(Sim800Error::Modem(_)) => None,
+                    Err(error) => return Err(error),
+                };
+            }
+
+            if call_query && !result.call_ready {
+                match self.send_one("AT+CCALR?") {
+                    Ok(Some(Response::Unknown {
+                        prefix: Some(prefix),
+                        raw,
+                    })) if prefix == "+CCALR" => result.call_ready = raw.trim() == "1",
+                    Ok(_) => {}
+                    // Firmware without call ready query, only unsolicited code shows readiness.
+                    Err(Sim800Error::Modem(_)) => call_query = false,
+                    Err(error) => return Err(error),
+                }
+            }
+
+            if !result.sms_ready {
+                result.sms_ready = match self.send("AT+CPMS?") {
+                    Ok(()) => true,
+                    Err(Sim800Error::Modem(_)) => false,
+                    Err(error) => return Err(error),
+                };
+            }
+
+            let waiting = match result.pin {
+                Some(PinStatus::Ready) => !result.sms_ready || (call_query && !result.call_ready),
+                Some(_) => false,
+                None => true,
+            };
+            let remaining = deadline.saturating_duration_since(Instant::now());
+
+            if !waiting || remaining.is_zero() {
+                break;
+            }
+
+            match codes.recv_timeout(remaining.min(READY_POLL_INTERVAL)) {
+                Ok(code) => result.update_response(&code),
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => return Err(RecvError.into()),
             }
         }
 
+        Ok(result)
+    }
+
+    /// Enables or disables command echo (`ATE1`/`ATE0`). Echoed commands are stripped from
+    /// responses in both states.
+    pub fn set_echo(&mut self, enabled: bool) -> Result<(), Sim800Error> {
+        self.send(if enabled { "ATE1" } else { "ATE0" })
+    }
+
+    /// Enables or disables delivery status reports for sent messages. Text mode messages use
+    /// parameters set with `AT+CSMP`, PDU mode messages set request flag directly. Reports are
+    /// shown as `+CDS` only if enabled with `AT+CNMI`.
+    pub fn set_status_report(&mut self, enabled: bool) -> Result<(), Sim800Error> {
+        let first_octet = if enabled {
+            SUBMIT_FIRST_OCTET | STATUS_REPORT_REQUEST
+        } else {
+            SUBMIT_FIRST_OCTET
+        };
+
+        self.send(&format!("AT+CSMP={}", first_octet))?;
+        self.status_report = enabled;
+
         Ok(())
     }
 
-    pub fn send_one(&mut self, command: &str) -> Result<Option<Response>, SendError<String>> {
-        let mut result = None;
+    pub fn delivery(&self) -> &DeliveryTracker {
+        &self.delivery
+    }
 
-        self.command_sender.send(command.into())?;
+    pub fn delivery_state(&self, reference: u8) -> Option<DeliveryState> {
+        self.delivery.state(reference)
+    }
+
+    /// Waits for status reports until all tracked messages leave pending state or timeout
+    /// expires.
+    pub fn wait_delivery(&mut self, timeout: Duration) -> Result<(), Sim800Error> {
+        let deadline = Instant::now() + timeout;
+
+        while self.delivery.has_pending() {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+
+            match self.unsolicited_receiver.recv_timeout(remaining) {
+                Ok(response) => {
+                    self.delivery.update_response(&response);
+                }
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(RecvTimeoutError::Disconnected) => return Err(RecvError.into()),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Subscribes to unsolicited result codes (`+CMTI`, `+CDS`, `RING` and others), every
+    /// subscriber receives own copy of each code. Subscription ends when receiver dropped.
+    pub fn subscribe(&self) -> Receiver<Response> {
+        let (sender, receiver) = mpsc::channel();
+
+        if let Ok(mut subscribers) = self.subscribers.lock() {
+            subscribers.push(sender);
+        }
+
+        receiver
+    }
+
+    /// Subscribes to all text received from module with parsed responses or parse errors, used
+    /// to display module traffic. Subscription ends when receiver dropped.
+    pub fn monitor(&self) -> Receiver<Received> {
+        let (sender, receiver) = mpsc::channel();
+
+        if let Ok(mut monitors) = self.monitors.lock() {
+            monitors.push(sender);
+        }
+
+        receiver
+    }
+
+    /// Updates delivery tracker from unsolicited codes received since last call.
+    fn process_unsolicited(&mut self) {
+        for response in self.unsolicited_receiver.try_iter() {
+            self.delivery.update_response(&response);
+        }
+    }
+
+    /// Sets response timeout for commands, commands with known long execution time wait at
+    /// least their maximum response time.
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = timeout;
+    }
+
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// Returns response timeout for command.
+    pub fn command_timeout(&self, command: &str) -> Duration {
+        COMMAND_TIMEOUTS
+            .iter()
+            .find(|(prefix, _)| command.starts_with(prefix))
+            .map(|&(_, seconds)| self.timeout.max(Duration::from_secs(seconds)))
+            .unwrap_or(self.timeout)
+    }
+
+    /// Sends command and passes every intermediate response to `handle` until final result code
+    /// received. Error result codes returned as `Sim800Error::Modem`, call result codes ending
+    /// `ATD` passed to `handle`.
+    fn execute<F>(&mut self, command: &str, mut handle: F) -> Result<(), Sim800Error>
+    where
+        F: FnMut(&Sender<Command>, Response) -> Result<(), Sim800Error>,
+    {
+        self.process_unsolicited();
+
+        for response in self.response_receiver.try_iter() {
+            warn!("<< Discarded: {:?}", response);
+        }
+
+        let deadline = Instant::now() + self.command_timeout(command);
+
+        self.command_sender.send(Command::Line(command.into()))?;
+
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let response = match self.response_receiver.recv_timeout(remaining) {
+                Ok(response) => response,
+                Err(RecvTimeoutError::Timeout) => return Err(Sim800Error::Timeout(command.into())),
+                Err(RecvTimeoutError::Disconnected) => return Err(RecvError.into()),
+            };
 
-        for response in &self.response_receiver {
             info!("<< {:?}", response);
 
+            self.delivery.update_response(&response);
+
             match response {
-                Response::Ok | Response::Error(_) => break,
-                _ => result = Some(response),
+                Response::Ok => return Ok(()),
+                Response::Error(error) => return Err(Sim800Error::Modem(error)),
+                response @ (Response::NoCarrier
+                | Response::Busy
+                | Response::NoAnswer
+                | Response::NoDialtone) => return handle(&self.command_sender, response),
+                Response::Empty => {}
+                response => handle(&self.command_sender, response)?,
             }
         }
+    }
+
+    pub fn send(&mut self, command: &str) -> Result<(), Sim800Error> {
+        self.execute(command, |_, _| Ok(()))
+    }
+
+    pub fn send_one(&mut self, command: &str) -> Result<Option<Response>, Sim800Error> {
+        let mut result = None;
+
+        self.execute(command, |_, response| {
+            result = Some(response);
+
+            Ok(())
+        })?;
 
         Ok(result)
     }
 
-    pub fn send_list(&mut self, command: &str) -> Result<Vec<Response>, SendError<String>> {
+    pub fn send_list(&mut self, command: &str) -> Result<Vec<Response>, Sim800Error> {
         let mut result = Vec::new();
 
-        self.command_sender.send(command.into())?;
+        self.execute(command, |_, response| {
+            result.push(response);
 
-        for response in &self.response_receiver {
-            info!("<< {:?}", response);
+            Ok(())
+        })?;
 
-            match response {
-                Response::Ok | Response::Error(_) => break,
-                _ => result.push(response),
+        Ok(result)
+    }
+
+    /// Sends command and returns intermediate responses like `send_list`. Data for `>` prompt
+    /// requested from `input`, prompt cancelled with ESC if it returns `None`.
+    pub fn send_input<F>(
+        &mut self,
+        command: &str,
+        mut input: F,
+    ) -> Result<Vec<Response>, Sim800Error>
+    where
+        F: FnMut() -> Option<String>,
+    {
+        let mut result = Vec::new();
+
+        self.execute(command, |command_sender, response| {
+            if let Response::Prompt = response {
+                match input() {
+                    Some(data) => command_sender.send(Command::Data(data))?,
+                    None => command_sender.send(Command::Cancel)?,
+                }
+            } else {
+                result.push(response);
+            }
+
+            Ok(())
+        })?;
+
+        Ok(result)
+    }
+
+    /// Sends SMS message in text mode, address and text are encoded with selected character set.
+    pub fn send_sms(&mut self, address: &str, text: &str) -> Result<Option<u8>, Sim800Error> {
+        let charset = self.character_set();
+        let reference = self.send_prompt(
+            format!(r#"AT+CMGS="{}""#, charset.encode(address)?),
+            charset.encode(text)?,
+        )?;
+
+        if let (true, Some(reference)) = (self.status_report, reference) {
+            self.delivery.register(reference);
+        }
+
+        Ok(reference)
+    }
+
+    /// Sends single PDU message (`AT+CMGF=0`), status report request flag is taken from PDU.
+    pub fn send_pdu(&mut self, pdu: &Pdu) -> Result<Option<u8>, Sim800Error> {
+        let data = pdu.encode()?;
+        let length = data.len() - data[0] as usize - 1;
+        let reference = self.send_prompt(format!("AT+CMGS={}", length), encode_hex(&data))?;
+
+        Ok(reference)
+    }
+
+    /// Returns number of messages required to send text with `send_text`, fails when text does
+    /// not fit into 255 parts.
+    pub fn segment_count(text: &str) -> Result<usize, PduError> {
+        segment_count(text)
+    }
+
+    /// Sends text as one or more SMS messages, selecting GSM 7 bit or UCS2 alphabet
+    /// automatically. Requires PDU message format (`AT+CMGF=0`). Returns references of sent
+    /// messages, sending stops at first failed part with its error or when module replies
+    /// without message reference.
+    pub fn send_text(&mut self, address: &str, text: &str) -> Result<Vec<u8>, Sim800Error> {
+        let mut result = Vec::new();
+        let reference = self.concatenation_reference;
+
+        self.concatenation_reference = reference.wrapping_add(1);
+
+        for mut submit in split_submit(&Address::parse(address), text, reference)? {
+            submit.status_report_request = self.status_report;
+
+            let error = match self.send_pdu(&Pdu::Submit(submit)) {
+                Ok(Some(reference)) => {
+                    if self.status_report {
+                        self.delivery.register(reference);
+                    }
+
+                    result.push(reference);
+
+                    continue;
+                }
+                Ok(None) => Sim800Error::MissingReply("AT+CMGS".into()),
+                Err(error) => error,
+            };
+
+            if !result.is_empty() {
+                warn!("Message parts sent before failure: {:?}", result);
             }
+
+            return Err(error);
         }
 
         Ok(result)
     }
 
+    fn send_prompt(&mut self, command: String, data: String) -> Result<Option<u8>, Sim800Error> {
+        let mut result = None;
+        let mut data = Some(data);
+
+        self.execute(&command, |command_sender, response| {
+            match response {
+                Response::Prompt => {
+                    if let Some(data) = data.take() {
+                        command_sender.send(Command::Data(data))?;
+                    }
+                }
+                Response::SendSmsMessage { reference } => result = Some(reference),
+                _ => {}
+            }
+
+            Ok(())
+        })?;
+
+        Ok(result)
+    }
+
+    /// Stops reader and writer threads, reader stops after next port read timeout.
     pub fn join(self) -> Result<(), Sim800Error> {
-        drop(self.port);
+        self.stop.store(true, Ordering::Relaxed);
+
         drop(self.command_sender);
         drop(self.response_receiver);
+        drop(self.unsolicited_receiver);
 
         self.command_write
             .join()
@@ -0,0 +1,61 @@
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Result as FmtResult;
+
+use crate::parser::PinStatus;
+use crate::parser::Response;
+
+/// Module state after initialization.
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct Readiness {
+    /// Number of `AT` commands sent until module answered, more than one while autobaud
+    /// synchronizes.
+    pub attempts: u32,
+    /// SIM card state, `None` if module could not report it yet.
+    pub pin: Option<PinStatus>,
+    pub call_ready: bool,
+    pub sms_ready: bool,
+}
+
+impl Readiness {
+    pub fn new(attempts: u32) -> Self {
+        Self {
+            attempts,
+            pin: None,
+            call_ready: false,
+            sms_ready: false,
+        }
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.pin == Some(PinStatus::Ready) && self.call_ready && self.sms_ready
+    }
+
+    /// Updates state from `Call Ready`, `SMS Ready` and `+CPIN` codes sent after power on.
+    pub fn update_response(&mut self, response: &Response) {
+        match response {
+            Response::CallReady => self.call_ready = true,
+            Response::SmsReady => self.sms_ready = true,
+            Response::PinStatus { status } => self.pin = Some(status.clone()),
+            _ => {}
+        }
+    }
+}
+
+impl Display for Readiness {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        let state = |ready| if ready { "ready" } else { "not ready" };
+
+        match &self.pin {
+            Some(pin) => write!(f, "SIM: {}", pin)?,
+            None => write!(f, "SIM: unknown")?,
+        }
+
+        write!(
+            f,
+            ", calls: {}, SMS: {}",
+            state(self.call_ready),
+            state(self.sms_ready)
+        )
+    }
+}
@@ -0,0 +1,722 @@
+use std::collections::BTreeMap;
+use std::collections::VecDeque;
+use std::io::Error as IoError;
+use std::io::ErrorKind;
+use std::io::Read;
+use std::io::Write;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::thread::Builder;
+use std::thread::JoinHandle;
+
+use time::OffsetDateTime;
+
+use crate::address::Address;
+use crate::parser::CharacterSet;
+use crate::parser::SmsMessageStatus;
+use crate::pdu::encode_hex;
+use crate::pdu::DataCoding;
+use crate::pdu::Pdu;
+use crate::pdu::SmsDeliver;
+use crate::pdu::UserData;
+use crate::transport::Transport;
+use crate::transport::TransportWrite;
+
+const CTRL_Z: u8 = 0x1A;
+const ESCAPE: u8 = 0x1B;
+/// Number of messages fitting into simulated SIM storage.
+const STORAGE_CAPACITY: usize = 30;
+
+/// Error injected into reply to next command.
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub enum Fault {
+    /// Garbage line sent before reply.
+    Garbled,
+    /// Command left without reply.
+    Timeout,
+    /// Command fails with `+CMS ERROR` code.
+    ServiceError(u16),
+}
+
+/// Message kept in simulated SMS storage.
+#[derive(Debug, Clone)]
+pub struct StoredMessage {
+    pub status: SmsMessageStatus,
+    pub address: Address,
+    pub timestamp: OffsetDateTime,
+    pub text: String,
+}
+
+/// Message sent with `AT+CMGS`.
+#[derive(Debug, Clone)]
+pub struct SentMessage {
+    pub reference: u8,
+    pub address: Address,
+    pub text: String,
+}
+
+#[derive(Debug)]
+enum Input {
+    Command,
+    TextData(String),
+    PduData,
+}
+
+/// Simulated SIM800 module. Input bytes passed to `feed` are processed as AT commands, replies
+/// returned as bytes to write back to host.
+#[derive(Debug)]
+pub struct Simulator {
+    messages: BTreeMap<u16, StoredMessage>,
+    sent: Vec<SentMessage>,
+    faults: VecDeque<Fault>,
+    input: Input,
+    buffer: Vec<u8>,
+    echo: bool,
+    text_mode: bool,
+    verbose_errors: bool,
+    character_set: CharacterSet,
+    registered: bool,
+    busy: bool,
+    active_call: bool,
+    next_reference: u8,
+}
+
+impl Default for Simulator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Simulator {
+    pub fn new() -> Self {
+        Self {
+            messages: BTreeMap::new(),
+            sent: Vec::new(),
+            faults: VecDeque::new(),
+            input: Input::Command,
+            buffer: Vec::new(),
+            echo: true,
+            text_mode: false,
+            verbose_errors: false,
+            character_set: CharacterSet::Ira,
+            registered: true,
+            busy: false,
+            active_call: false,
+            next_reference: 0,
+        }
+    }
+
+    /// Stores message in first free storage slot, returns its index.
+    pub fn add_message(
+        &mut self,
+        status: SmsMessageStatus,
+        address: &str,
+        timestamp: OffsetDateTime,
+        text: &str,
+    ) -> u16 {
+        let index = (1..)
+            .find(|index| !self.messages.contains_key(index))
+            .unwrap_or_default();
+
+        self.messages.insert(
+            index,
+            StoredMessage {
+                status,
+                address: Address::parse(address),
+                timestamp,
+                text: text.into(),
+            },
+        );
+
+        index
+    }
+
+    /// Stores new unread message, returns `+CMTI` indication to send to host.
+    pub fn receive_message(
+        &mut self,
+        address: &str,
+        timestamp: OffsetDateTime,
+        text: &str,
+    ) -> Vec<u8> {
+        let index = self.add_message(SmsMessageStatus::ReceivedUnread, address, timestamp, text);
+
+        response(&format!(r#"+CMTI: "SM",{}"#, index))
+    }
+
+    pub fn messages(&self) -> &BTreeMap<u16, StoredMessage> {
+        &self.messages
+    }
+
+    pub fn sent(&self) -> &[SentMessage] {
+        &self.sent
+    }
+
+    pub fn set_registered(&mut self, registered: bool) {
+        self.registered = registered;
+    }
+
+    /// Makes called numbers busy, voice calls fail with `BUSY`.
+    pub fn set_busy(&mut self, busy: bool) {
+        self.busy = busy;
+    }
+
+    /// Injects fault into reply to next command, faults applied in order of injection.
+    pub fn inject_fault(&mut self, fault: Fault) {
+        self.faults.push_back(fault);
+    }
+
+    /// Processes bytes received from host and returns reply.
+    pub fn feed(&mut self, data: &[u8]) -> Vec<u8> {
+        let mut result = Vec::new();
+
+        for &byte in data {
+            match self.input {
+                Input::Command => {
+                    if self.echo {
+                        result.push(byte);
+                    }
+
+                    match byte {
+                        b'\r' => {
+                            let line = String::from_utf8_lossy(&self.buffer).trim().to_string();
+
+                            self.buffer.clear();
+
+                            if !line.is_empty() {
+                                result.extend(self.reply(&line));
+                            }
+                        }
+                        b'\n' => {}
+                        _ => self.buffer.push(byte),
+                    }
+                }
+                Input::TextData(_) | Input::PduData => match byte {
+                    CTRL_Z => result.extend(self.complete_send()),
+                    ESCAPE => {
+                        self.buffer.clear();
+                        self.input = Input::Command;
+
+                        result.extend(response("OK"));
+                    }
+                    // Line feed following command terminator is not part of message.
+                    b'\n' if self.buffer.is_empty() => {}
+                    _ => {
+                        // Message text echoed like commands, without terminating Ctrl-Z.
+                        if self.echo {
+                            result.push(byte);
+                        }
+
+                        self.buffer.push(byte);
+                    }
+                },
+            }
+        }
+
+        result
+    }
+
+    fn reply(&mut self, line: &str) -> Vec<u8> {
+        let fault = self.faults.pop_front();
+        let mut result = Vec::new();
+
+        match fault {
+            Some(Fault::Timeout) => return result,
+            Some(Fault::ServiceError(code)) => return self.service_error(code),
+            Some(Fault::Garbled) => result.extend(b"\r\n+C\xFF#\x02GARBLED\r\n"),
+            None => {}
+        }
+
+        let upper = line.to_uppercase();
+        let reply = match upper.as_str() {
+            "AT" | "ATZ" => Some(response("OK")),
+            "ATE0" | "ATE1" => {
+                self.echo = upper == "ATE1";
+
+                Some(response("OK"))
+            }
+            "AT+CREG?" => Some(information(&format!(
+                "+CREG: 0,{}",
+                if self.registered { 1 } else { 2 }
+            ))),
+            "AT+CMGF?" => Some(information(&format!("+CMGF: {}", u8::from(self.text_mode)))),
+            "AT+CMGF=0" | "AT+CMGF=1" => {
+                self.text_mode = upper == "AT+CMGF=1";
+
+                Some(response("OK"))
+            }
+            "AT+CSCS?" => Some(information(&format!(r#"+CSCS: "{}""#, self.character_set))),
+            "AT+CPIN?" => Some(information("+CPIN: READY")),
+            "AT+CCALR?" => Some(information("+CCALR: 1")),
+            "AT+CSQ" => Some(information("+CSQ: 20,0")),
+            "AT+CBC" => Some(information("+CBC: 0,85,4100")),
+            "AT+CPAS" => Some(information(&format!(
+                "+CPAS: {}",
+                if self.active_call { 4 } else { 0 }
+            ))),
+            "AT+COPS?" if self.registered => Some(information(r#"+COPS: 0,0,"Simulator""#)),
+            "AT+COPS?" => Some(information("+COPS: 0")),
+            "ATH" => {
+                self.active_call = false;
+
+                Some(response("OK"))
+            }
+            "AT+CPMS?" => {
+                let used = self.messages.len();
+
+                Some(information(&format!(
+                    r#"+CPMS: "SM",{0},{1},"SM",{0},{1},"SM",{0},{1}"#,
+                    used, STORAGE_CAPACITY
+                )))
+            }
+            _ => None,
+        };
+
+        result.extend(match reply {
+            Some(reply) => reply,
+            None => self.reply_parameters(line, &upper),
+        });
+        result
+    }
+
+    fn reply_parameters(&mut self, line: &str, upper: &str) -> Vec<u8> {
+        if let Some(value) = upper.strip_prefix("AT+CMEE=") {
+            self.verbose_errors = value != "0";
+
+            response("OK")
+        } else if let Some(value) = line.get(8..).filter(|_| upper.starts_with("AT+CSCS=")) {
+            match value.trim_matches('"').parse() {
+                Ok(charset) => {
+                    self.character_set = charset;
+
+                    response("OK")
+                }
+                Err(_) => self.service_error(303),
+            }
+        } else if ["AT+CSDH=", "AT+CNMI=", "AT+CSMP=", "AT+CPMS=", "AT+IPR="]
+            .iter()
+            .any(|prefix| upper.starts_with(prefix))
+        {
+            response("OK")
+        } else if let Some(arguments) = upper.strip_prefix("AT+CMGL=") {
+            let (filter, mode) = arguments.split_once(',').unwrap_or((arguments, "0"));
+
+            self.list_messages(filter.trim_matches('"'), mode == "1")
+        } else if let Some(index) = upper.strip_prefix("AT+CMGR=") {
+            match index.parse() {
+                Ok(index) => self.read_message(index),
+                Err(_) => self.service_error(321),
+            }
+        } else if let Some(arguments) = upper.strip_prefix("AT+CMGD=") {
+            self.delete_messages(arguments)
+        } else if let Some(argument) = upper.strip_prefix("AT+CMGDA=") {
+            self.delete_all(argument.trim_matches('"'))
+        } else if let Some(argument) = line.get(8..).filter(|_| upper.starts_with("AT+CMGS=")) {
+            self.start_send(argument)
+        } else if let Some(arguments) = line.get(8..).filter(|_| upper.starts_with("AT+CUSD=")) {
+            self.unstructured_data(arguments)
+        } else if upper.starts_with("ATD") && upper.ends_with(';') {
+            // Voice call fails before `OK` with call result code as final result.
+            if !self.registered {
+                response("NO CARRIER")
+            } else if self.busy {
+                response("BUSY")
+            } else {
+                self.active_call = true;
+
+                response("OK")
+            }
+        } else {
+            response("ERROR")
+        }
+    }
+
+    /// Replies to USSD request with `+CUSD` code repeating request string.
+    fn unstructured_data(&mut self, arguments: &str) -> Vec<u8> {
+        let mut fields = arguments.splitn(3, ',');
+        let request = fields.nth(1).map(|request| request.trim_matches('"'));
+
+        match request {
+            Some(request) if self.registered => {
+                let request = self.character_set.decode(request);
+                let text = match self
+                    .character_set
+                    .encode(&format!("Request {} accepted", request))
+                {
+                    Ok(text) => text,
+                    Err(_) => return self.service_error(305),
+                };
+                let mut result = response("OK");
+
+                result.extend(response(&format!(r#"+CUSD: 0,"{}",15"#, text)));
+                result
+            }
+            Some(_) => self.service_error(331),
+            None => response("OK"),
+        }
+    }
+
+    /// Lists messages matching filter, unread messages marked read unless `keep_status` set.
+    fn list_messages(&mut self, filter: &str, keep_status: bool) -> Vec<u8> {
+        let status = match filter {
+            "ALL" | "4" => None,
+            "REC UNREAD" | "0" => Some(SmsMessageStatus::ReceivedUnread),
+            "REC READ" | "1" => Some(SmsMessageStatus::ReceivedRead),
+            "STO UNSENT" | "2" => Some(SmsMessageStatus::StoredUnsent),
+            "STO SENT" | "3" => Some(SmsMessageStatus::StoredSent),
+            _ => return self.service_error(302),
+        };
+
+        // Text mode accepts only status names, PDU mode only numeric status.
+        if filter.parse::<u8>().is_ok() == self.text_mode {
+            return self.service_error(302);
+        }
+
+        let mut result = Vec::new();
+        let indices: Vec<u16> = self
+            .messages
+            .iter()
+            .filter(|(_, message)| status.is_none() || status.as_ref() == Some(&message.status))
+            .map(|(&index, _)| index)
+            .collect();
+
+        for index in indices {
+            match self.format_message(&format!("+CMGL: {},", index), index) {
+                Ok(data) => result.extend(data),
+                Err(code) => return self.service_error(code),
+            }
+
+            if !keep_status {
+                self.mark_read(index);
+            }
+        }
+
+        result.extend(response("OK"));
+        result
+    }
+
+    fn read_message(&mut self, index: u16) -> Vec<u8> {
+        if !self.messages.contains_key(&index) {
+            return response("OK");
+        }
+
+        match self.format_message("+CMGR: ", index) {
+            Ok(mut result) => {
+                self.mark_read(index);
+
+                result.extend(response("OK"));
+                result
+            }
+            Err(code) => self.service_error(code),
+        }
+    }
+
+    fn mark_read(&mut self, index: u16) {
+        if let Some(message) = self.messages.get_mut(&index) {
+            if message.status == SmsMessageStatus::ReceivedUnread {
+                message.status = SmsMessageStatus::ReceivedRead;
+            }
+        }
+    }
+
+    /// Formats stored message in selected message format, returns `+CMS ERROR` code on failure.
+    fn format_message(&self, prefix: &str, index: u16) -> Result<Vec<u8>, u16> {
+        let message = self.messages.get(&index).ok_or(321_u16)?;
+
+        if self.text_mode {
+            let charset = self.character_set;
+            let address = charset
+                .encode(&message.address.to_string())
+                .map_err(|_| 305_u16)?;
+            let text = charset.encode(&message.text).map_err(|_| 305_u16)?;
+            let header = format!(
+                r#"{}"{}","{}",,"{}",{},{}"#,
+                prefix,
+                status_text(&message.status),
+                address,
+                format_timestamp(message.timestamp),
+                message.address.type_of_address(),
+                message.text.chars().count(),
+            );
+
+            Ok(format!("\r\n{}\r\n{}\r\n", header, text).into_bytes())
+        } else {
+            let deliver = SmsDeliver {
+                smsc: None,
+                more_messages: false,
+                reply_path: false,
+                status_report_indication: false,
+                originator: message.address.clone(),
+                protocol_identifier: 0,
+                data_coding: DataCoding::for_text(&message.text),
+                timestamp: message.timestamp,
+                user_data: UserData::text(&message.text),
+            };
+            let data = Pdu::Deliver(deliver).encode().map_err(|_| 500_u16)?;
+            let header = format!(
+                "{}{},,{}",
+                prefix,
+                status_code(&message.status),
+                data.len() - data[0] as usize - 1,
+            );
+
+            Ok(format!("\r\n{}\r\n{}\r\n", header, encode_hex(&data)).into_bytes())
+        }
+    }
+
+    fn delete_messages(&mut self, arguments: &str) -> Vec<u8> {
+        let mut arguments = arguments.split(',').map(str::trim);
+        let index = arguments.next().and_then(|index| index.parse::<u16>().ok());
+        let flag = arguments
+            .next()
+            .map(|flag| flag.parse::<u8>().ok())
+            .unwrap_or(Some(0));
+
+        match (index, flag) {
+            (Some(index), Some(0)) => {
+                self.messages.remove(&index);
+            }
+            (_, Some(flag @ 1..=4)) => self.messages.retain(|_, message| {
+                let keep = match message.status {
+                    SmsMessageStatus::ReceivedUnread => 4,
+                    SmsMessageStatus::ReceivedRead => 1,
+                    SmsMessageStatus::StoredSent => 2,
+                    SmsMessageStatus::StoredUnsent => 3,
+                };
+
+                flag < keep
+            }),
+            _ => return self.service_error(321),
+        }
+
+        response("OK")
+    }
+
+    /// Removes messages by type of `AT+CMGDA`, given as number or text.
+    fn delete_all(&mut self, argument: &str) -> Vec<u8> {
+        let statuses: &[SmsMessageStatus] = match argument {
+            "1" | "DEL READ" => &[SmsMessageStatus::ReceivedRead],
+            "2" | "DEL UNREAD" => &[SmsMessageStatus::ReceivedUnread],
+            "3" | "DEL SENT" => &[SmsMessageStatus::StoredSent],
+            "4" | "DEL UNSENT" => &[SmsMessageStatus::StoredUnsent],
+            "5" | "DEL INBOX" => &[
+                SmsMessageStatus::ReceivedRead,
+                SmsMessageStatus::ReceivedUnread,
+            ],
+            "6" | "DEL ALL" => &[
+                SmsMessageStatus::ReceivedRead,
+                SmsMessageStatus::ReceivedUnread,
+                SmsMessageStatus::StoredSent,
+                SmsMessageStatus::StoredUnsent,
+            ],
+            _ => return self.service_error(302),
+        };
+
+        self.messages
+            .retain(|_, message| !statuses.contains(&message.status));
+
+        response("OK")
+    }
+
+    fn start_send(&mut self, argument: &str) -> Vec<u8> {
+        if !self.registered {
+            return self.service_error(331);
+        }
+
+        if self.text_mode {
+            let address = self.character_set.decode(argument.trim_matches('"'));
+
+            self.input = Input::TextData(address);
+        } else if argument.parse::<u8>().is_ok() {
+            self.input = Input::PduData;
+        } else {
+            return self.service_error(304);
+        }
+
+        self.buffer.clear();
+
+        b"\r\n> ".to_vec()
+    }
+
+    fn complete_send(&mut self) -> Vec<u8> {
+        let data = String::from_utf8_lossy(&self.buffer).into_owned();
+        let input = std::mem::replace(&mut self.input, Input::Command);
+
+        self.buffer.clear();
+
+        let (address, text) = match input {
+            Input::TextData(address) => {
+                (Address::parse(&address), self.character_set.decode(&data))
+            }
+            _ => match Pdu::from_hex(data.trim()) {
+                Ok(Pdu::Submit(submit)) => (submit.destination, submit.user_data.to_text()),
+                _ => return self.service_error(304),
+            },
+        };
+        let reference = self.next_reference;
+
+        self.next_reference = reference.wrapping_add(1);
+        self.sent.push(SentMessage {
+            reference,
+            address,
+            text,
+        });
+
+        information(&format!("+CMGS: {}", reference))
+    }
+
+    fn service_error(&self, code: u16) -> Vec<u8> {
+        if self.verbose_errors {
+            response(&format!("+CMS ERROR: {}", code))
+        } else {
+            response("ERROR")
+        }
+    }
+
+    /// Runs simulator over transport in separate thread.
+    pub fn spawn(self, transport: Box<dyn Transport>) -> Result<SimulatorHandle, IoError> {
+        let (mut reader, writer) = transport.split()?;
+        let simulator = Arc::new(Mutex::new(self));
+        let writer = Arc::new(Mutex::new(writer));
+        let stop = Arc::new(AtomicBool::new(false));
+        let state = simulator.clone();
+        let output = writer.clone();
+        let stopped = stop.clone();
+        let thread = Builder::new().spawn(move || {
+            let mut buffer = [0; 256];
+
+            loop {
+                let count = match reader.read(&mut buffer) {
+                    Err(error)
+                        if error.kind() == ErrorKind::TimedOut
+                            || error.kind() == ErrorKind::WouldBlock =>
+                    {
+                        if stopped.load(Ordering::Relaxed) {
+                            break;
+                        }
+
+                        continue;
+                    }
+                    result => result?,
+                };
+
+                if count == 0 {
+                    break;
+                }
+
+                let reply = lock(&state).feed(&buffer[..count]);
+                let mut output = lock(&output);
+
+                output.write_all(&reply)?;
+                output.flush()?;
+            }
+
+            Ok(())
+        })?;
+
+        Ok(SimulatorHandle {
+            simulator,
+            writer,
+            stop,
+            thread,
+        })
+    }
+}
+
+/// Running simulator, allows to change state and send unsolicited result codes.
+pub struct SimulatorHandle {
+    simulator: Arc<Mutex<Simulator>>,
+    writer: Arc<Mutex<TransportWrite>>,
+    stop: Arc<AtomicBool>,
+    thread: JoinHandle<Result<(), IoError>>,
+}
+
+impl SimulatorHandle {
+    pub fn simulator(&self) -> MutexGuard<'_, Simulator> {
+        lock(&self.simulator)
+    }
+
+    /// Sends unsolicited result code line to host.
+    pub fn emit(&self, line: &str) -> Result<(), IoError> {
+        self.write(&response(line))
+    }
+
+    /// Stores new message and sends `+CMTI` indication to host.
+    pub fn receive_message(
+        &self,
+        address: &str,
+        timestamp: OffsetDateTime,
+        text: &str,
+    ) -> Result<(), IoError> {
+        let indication = self.simulator().receive_message(address, timestamp, text);
+
+        self.write(&indication)
+    }
+
+    fn write(&self, data: &[u8]) -> Result<(), IoError> {
+        let mut writer = lock(&self.writer);
+
+        writer.write_all(data)?;
+        writer.flush()
+    }
+
+    /// Stops simulator thread after next transport read timeout.
+    pub fn join(self) -> Result<(), IoError> {
+        self.stop.store(true, Ordering::Relaxed);
+
+        self.thread.join().expect("Simulator thread panicked")
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|error| error.into_inner())
+}
+
+/// Formats response line (result code or unsolicited result code) surrounded with line breaks.
+fn response(line: &str) -> Vec<u8> {
+    format!("\r\n{}\r\n", line).into_bytes()
+}
+
+/// Formats information response followed by `OK` final result code.
+fn information(line: &str) -> Vec<u8> {
+    let mut result = response(line);
+
+    result.extend(response("OK"));
+    result
+}
+
+fn status_text(status: &SmsMessageStatus) -> &'static str {
+    match status {
+        SmsMessageStatus::ReceivedUnread => "REC UNREAD",
+        SmsMessageStatus::ReceivedRead => "REC READ",
+        SmsMessageStatus::StoredUnsent => "STO UNSENT",
+        SmsMessageStatus::StoredSent => "STO SENT",
+    }
+}
+
+fn status_code(status: &SmsMessageStatus) -> u8 {
+    match status {
+        SmsMessageStatus::ReceivedUnread => 0,
+        SmsMessageStatus::ReceivedRead => 1,
+        SmsMessageStatus::StoredUnsent => 2,
+        SmsMessageStatus::StoredSent => 3,
+    }
+}
+
+/// Formats service center time stamp as `yy/MM/dd,hh:mm:ss±zz`, zone in quarters of hour.
+fn format_timestamp(timestamp: OffsetDateTime) -> String {
+    let quarters = timestamp.offset().whole_seconds() / (15 * 60);
+
+    format!(
+        "{:02}/{:02}/{:02},{:02}:{:02}:{:02}{:+03}",
+        timestamp.year() % 100,
+        u8::from(timestamp.month()),
+        timestamp.day(),
+        timestamp.hour(),
+        timestamp.minute(),
+        timestamp.second(),
+        quarters,
+    )
+}
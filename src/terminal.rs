@@ -0,0 +1,121 @@
+use rustyline::error::ReadlineError;
+use rustyline::DefaultEditor;
+use rustyline::ExternalPrinter;
+use sim800_reader::parser::Response;
+use sim800_reader::sim800::Received;
+use sim800_reader::sim800::Sim800;
+use sim800_reader::sim800::Sim800Error;
+use std::error::Error;
+use std::io::IsTerminal;
+use std::path::Path;
+use std::process::ExitCode;
+use std::sync::mpsc::Receiver;
+use std::thread::Builder;
+
+const PROMPT: &str = "sim800> ";
+
+/// Prompt for data requested by module with `>`, like message text for `AT+CMGS`.
+const DATA_PROMPT: &str = "data> ";
+
+const HIGHLIGHT: &str = "\x1b[1;31m";
+
+const RESET: &str = "\x1b[0m";
+
+/// Formats received text line by line followed by parsed response or parse error, unparsed and
+/// unknown lines highlighted if `color` set. Returns `None` for empty lines.
+fn format_received(received: &Received, color: bool) -> Option<String> {
+    let (text, details, unparsed) = match received {
+        Received::Parsed { text, response } => (
+            text,
+            format!("{:?}", response),
+            matches!(response, Response::Unknown { .. }),
+        ),
+        Received::Unparsed { text, error } => (text, error.clone(), true),
+    };
+    let mut result = String::new();
+
+    for line in text.split(['\r', '\n']).filter(|line| !line.is_empty()) {
+        match (unparsed, color) {
+            (false, _) => result.push_str(&format!("<< {}\n", line)),
+            (true, false) => result.push_str(&format!("!! {}\n", line)),
+            (true, true) => result.push_str(&format!("{}!! {}{}\n", HIGHLIGHT, line, RESET)),
+        }
+    }
+
+    if result.is_empty() {
+        return None;
+    }
+
+    result.push_str(&format!("   {}", details));
+
+    Some(result)
+}
+
+/// Prints received text until module connection closed. Printer used while line editor active, so
+/// output does not break typed line.
+fn print_received<P>(receiver: Receiver<Received>, mut printer: Option<P>, color: bool)
+where
+    P: ExternalPrinter,
+{
+    for received in receiver {
+        let Some(text) = format_received(&received, color) else {
+            continue;
+        };
+
+        let printed = match &mut printer {
+            Some(printer) => printer.print(format!("{}\n", text)).is_ok(),
+            None => false,
+        };
+
+        if !printed {
+            println!("{}", text);
+        }
+    }
+}
+
+/// Reads AT commands from terminal and sends them to module until end of input. All text
+/// received from module shown with parsed responses, including unsolicited result codes.
+pub fn terminal(sim800: &mut Sim800, history: Option<&Path>) -> Result<ExitCode, Box<dyn Error>> {
+    let mut editor = DefaultEditor::new()?;
+
+    if let Some(path) = history {
+        if path.exists() {
+            editor.load_history(path)?;
+        }
+    }
+
+    let receiver = sim800.monitor();
+    let printer = editor.create_external_printer().ok();
+    let color = std::io::stdout().is_terminal();
+
+    Builder::new().spawn(move || print_received(receiver, printer, color))?;
+
+    loop {
+        let command = match editor.readline(PROMPT) {
+            Ok(command) => command.trim().to_string(),
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => break,
+            Err(error) => return Err(error.into()),
+        };
+
+        if command.is_empty() {
+            continue;
+        }
+
+        editor.add_history_entry(&command)?;
+
+        let result = sim800.send_input(&command, || editor.readline(DATA_PROMPT).ok());
+
+        match result {
+            Ok(_) | Err(Sim800Error::Modem(_)) => {}
+            Err(error @ Sim800Error::Timeout(_)) => eprintln!("{}", error),
+            Err(error) => return Err(error.into()),
+        }
+    }
+
+    if let Some(path) = history {
+        editor.save_history(path)?;
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
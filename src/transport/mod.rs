@@ -0,0 +1,32 @@
+mod pipe;
+#[cfg(unix)]
+mod pty;
+mod record;
+mod serial;
+mod tcp;
+
+pub use pipe::PipeTransport;
+#[cfg(unix)]
+pub use pty::PtyTransport;
+pub use record::RecordTransport;
+pub use record::ReplayTransport;
+pub use serial::SerialTransport;
+pub use tcp::TcpTransport;
+
+use std::io::Error as IoError;
+use std::io::Read;
+use std::io::Write;
+
+/// Reading half of transport.
+pub type TransportRead = Box<dyn Read + Send>;
+
+/// Writing half of transport.
+pub type TransportWrite = Box<dyn Write + Send>;
+
+/// Byte stream connected to SIM800 module. Reading half should return `TimedOut` or
+/// `WouldBlock` error periodically when no data available, otherwise reader thread can not be
+/// stopped until next byte received.
+pub trait Transport: Send {
+    /// Splits transport into independent reading and writing halves.
+    fn split(self: Box<Self>) -> Result<(TransportRead, TransportWrite), IoError>;
+}
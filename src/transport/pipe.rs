@@ -0,0 +1,123 @@
+use std::io::Error as IoError;
+use std::io::ErrorKind;
+use std::io::Read;
+use std::io::Result as IoResult;
+use std::io::Write;
+use std::sync::mpsc;
+use std::sync::mpsc::Receiver;
+use std::sync::mpsc::RecvTimeoutError;
+use std::sync::mpsc::Sender;
+use std::time::Duration;
+
+use super::Transport;
+use super::TransportRead;
+use super::TransportWrite;
+
+/// In-memory transport, one end of bidirectional pipe created with `PipeTransport::pair`.
+#[derive(Debug)]
+pub struct PipeTransport {
+    reader: PipeReader,
+    writer: PipeWriter,
+}
+
+impl PipeTransport {
+    /// Creates connected pipe ends, data written to one end can be read from another.
+    pub fn pair(read_timeout: Duration) -> (Self, Self) {
+        let (left_sender, left_receiver) = mpsc::channel();
+        let (right_sender, right_receiver) = mpsc::channel();
+
+        (
+            Self {
+                reader: PipeReader::new(left_receiver, read_timeout),
+                writer: PipeWriter {
+                    sender: right_sender,
+                },
+            },
+            Self {
+                reader: PipeReader::new(right_receiver, read_timeout),
+                writer: PipeWriter {
+                    sender: left_sender,
+                },
+            },
+        )
+    }
+}
+
+impl Read for PipeTransport {
+    fn read(&mut self, buffer: &mut [u8]) -> IoResult<usize> {
+        self.reader.read(buffer)
+    }
+}
+
+impl Write for PipeTransport {
+    fn write(&mut self, buffer: &[u8]) -> IoResult<usize> {
+        self.writer.write(buffer)
+    }
+
+    fn flush(&mut self) -> IoResult<()> {
+        self.writer.flush()
+    }
+}
+
+impl Transport for PipeTransport {
+    fn split(self: Box<Self>) -> Result<(TransportRead, TransportWrite), IoError> {
+        Ok((Box::new(self.reader), Box::new(self.writer)))
+    }
+}
+
+#[derive(Debug)]
+struct PipeReader {
+    receiver: Receiver<Vec<u8>>,
+    read_timeout: Duration,
+    pending: Vec<u8>,
+}
+
+impl PipeReader {
+    fn new(receiver: Receiver<Vec<u8>>, read_timeout: Duration) -> Self {
+        Self {
+            receiver,
+            read_timeout,
+            pending: Vec::new(),
+        }
+    }
+}
+
+impl Read for PipeReader {
+    /// Returns `TimedOut` error if no data written during read timeout and end of stream when
+    /// other end closed.
+    fn read(&mut self, buffer: &mut [u8]) -> IoResult<usize> {
+        if self.pending.is_empty() {
+            match self.receiver.recv_timeout(self.read_timeout) {
+                Ok(data) => self.pending = data,
+                Err(RecvTimeoutError::Timeout) => return Err(ErrorKind::TimedOut.into()),
+                Err(RecvTimeoutError::Disconnected) => return Ok(0),
+            }
+        }
+
+        let count = buffer.len().min(self.pending.len());
+
+        buffer[..count].copy_from_slice(&self.pending[..count]);
+        self.pending.drain(..count);
+
+        Ok(count)
+    }
+}
+
+#[derive(Debug)]
+struct PipeWriter {
+    sender: Sender<Vec<u8>>,
+}
+
+impl Write for PipeWriter {
+    fn write(&mut self, buffer: &[u8]) -> IoResult<usize> {
+        self.sender
+            .send(buffer.to_vec())
+            .map_err(|_| IoError::from(ErrorKind::BrokenPipe))?;
+
+        Ok(buffer.len())
+    }
+
+    fn flush(&mut self) -> IoResult<()> {
+        Ok(())
+    }
+}
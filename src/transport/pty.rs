@@ -0,0 +1,125 @@
+use std::ffi::CStr;
+use std::fs::File;
+use std::fs::OpenOptions;
+use std::io::Error as IoError;
+use std::io::ErrorKind;
+use std::io::Read;
+use std::io::Result as IoResult;
+use std::os::fd::AsRawFd;
+use std::os::fd::FromRawFd;
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use super::Transport;
+use super::TransportRead;
+use super::TransportWrite;
+
+/// Unix pseudo terminal transport. Transport owns master side, other program (like terminal
+/// emulator or this utility running with `--serial-port`) opens slave device path.
+#[derive(Debug)]
+pub struct PtyTransport {
+    master: File,
+    slave: File,
+    slave_path: PathBuf,
+    read_timeout: Duration,
+}
+
+impl PtyTransport {
+    /// Creates new pseudo terminal in raw mode.
+    pub fn open(read_timeout: Duration) -> Result<Self, IoError> {
+        let master = unsafe {
+            let fd = libc::posix_openpt(libc::O_RDWR | libc::O_NOCTTY);
+
+            if fd < 0 {
+                return Err(IoError::last_os_error());
+            }
+
+            File::from_raw_fd(fd)
+        };
+
+        let slave_path = unsafe {
+            if libc::grantpt(master.as_raw_fd()) != 0 || libc::unlockpt(master.as_raw_fd()) != 0 {
+                return Err(IoError::last_os_error());
+            }
+
+            let name = libc::ptsname(master.as_raw_fd());
+
+            if name.is_null() {
+                return Err(IoError::last_os_error());
+            }
+
+            PathBuf::from(CStr::from_ptr(name).to_string_lossy().into_owned())
+        };
+
+        // Slave kept open, otherwise master reads fail until other program opens it.
+        let slave = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .custom_flags(libc::O_NOCTTY)
+            .open(&slave_path)?;
+
+        unsafe {
+            let mut attributes = std::mem::zeroed::<libc::termios>();
+
+            if libc::tcgetattr(slave.as_raw_fd(), &mut attributes) != 0 {
+                return Err(IoError::last_os_error());
+            }
+
+            libc::cfmakeraw(&mut attributes);
+
+            if libc::tcsetattr(slave.as_raw_fd(), libc::TCSANOW, &attributes) != 0 {
+                return Err(IoError::last_os_error());
+            }
+        }
+
+        Ok(Self {
+            master,
+            slave,
+            slave_path,
+            read_timeout,
+        })
+    }
+
+    pub fn slave_path(&self) -> &Path {
+        &self.slave_path
+    }
+}
+
+impl Transport for PtyTransport {
+    fn split(self: Box<Self>) -> Result<(TransportRead, TransportWrite), IoError> {
+        let reader = PtyReader {
+            master: self.master.try_clone()?,
+            _slave: self.slave,
+            read_timeout: self.read_timeout,
+        };
+
+        Ok((Box::new(reader), Box::new(self.master)))
+    }
+}
+
+#[derive(Debug)]
+struct PtyReader {
+    master: File,
+    _slave: File,
+    read_timeout: Duration,
+}
+
+impl Read for PtyReader {
+    /// Waits for data at most read timeout, returns `TimedOut` error if no data available.
+    fn read(&mut self, buffer: &mut [u8]) -> IoResult<usize> {
+        let mut descriptor = libc::pollfd {
+            fd: self.master.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        let timeout = self.read_timeout.as_millis().min(i32::MAX as u128) as i32;
+
+        match unsafe { libc::poll(&mut descriptor, 1, timeout) } {
+            0 => Err(ErrorKind::TimedOut.into()),
+            result if result < 0 => Err(IoError::last_os_error()),
+            _ => self.master.read(buffer),
+        }
+    }
+}
@@ -0,0 +1,334 @@
+use log::warn;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::Error as IoError;
+use std::io::ErrorKind;
+use std::io::Read;
+use std::io::Result as IoResult;
+use std::io::Write;
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::Condvar;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::time::Duration;
+use std::time::Instant;
+
+use crate::pdu::decode_hex;
+use crate::pdu::encode_hex;
+
+use super::Transport;
+use super::TransportRead;
+use super::TransportWrite;
+
+const SENT: &str = ">";
+const RECEIVED: &str = "<";
+
+/// Transcript writer. Every line contains milliseconds since start, direction (`>` sent to module,
+/// `<` received from module) and data as hexadecimal string. Chunks of the same direction joined
+/// until end of line, so transcript keeps one line per command or response line.
+struct Transcript {
+    output: Box<dyn Write + Send>,
+    start: Instant,
+    pending: Option<(&'static str, u128, Vec<u8>)>,
+}
+
+impl Transcript {
+    fn append(&mut self, direction: &'static str, data: &[u8]) -> IoResult<()> {
+        if matches!(self.pending, Some((pending, ..)) if pending != direction) {
+            self.flush()?;
+        }
+
+        let elapsed = self.start.elapsed().as_millis();
+        let (_, _, buffer) = self
+            .pending
+            .get_or_insert_with(|| (direction, elapsed, Vec::new()));
+
+        buffer.extend_from_slice(data);
+
+        if buffer.ends_with(b"\n") {
+            self.flush()?;
+        }
+
+        Ok(())
+    }
+
+    fn flush(&mut self) -> IoResult<()> {
+        if let Some((direction, elapsed, data)) = self.pending.take() {
+            writeln!(
+                self.output,
+                "{} {} {}",
+                elapsed,
+                direction,
+                encode_hex(&data)
+            )?;
+            self.output.flush()?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for Transcript {
+    fn drop(&mut self) {
+        if let Err(error) = self.flush() {
+            warn!("Failed to write transcript: {}", error);
+        }
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|error| error.into_inner())
+}
+
+/// Transport wrapper writing all traffic of inner transport to transcript.
+pub struct RecordTransport {
+    inner: Box<dyn Transport>,
+    transcript: Arc<Mutex<Transcript>>,
+}
+
+impl RecordTransport {
+    pub fn new(inner: Box<dyn Transport>, output: Box<dyn Write + Send>) -> Self {
+        Self {
+            inner,
+            transcript: Arc::new(Mutex::new(Transcript {
+                output,
+                start: Instant::now(),
+                pending: None,
+            })),
+        }
+    }
+
+    pub fn create<P>(inner: Box<dyn Transport>, path: P) -> Result<Self, IoError>
+    where
+        P: AsRef<Path>,
+    {
+        Ok(Self::new(inner, Box::new(File::create(path)?)))
+    }
+}
+
+impl Transport for RecordTransport {
+    fn split(self: Box<Self>) -> Result<(TransportRead, TransportWrite), IoError> {
+        let (reader, writer) = self.inner.split()?;
+
+        Ok((
+            Box::new(RecordReader {
+                inner: reader,
+                transcript: self.transcript.clone(),
+            }),
+            Box::new(RecordWriter {
+                inner: writer,
+                transcript: self.transcript,
+            }),
+        ))
+    }
+}
+
+struct RecordReader {
+    inner: TransportRead,
+    transcript: Arc<Mutex<Transcript>>,
+}
+
+impl Read for RecordReader {
+    fn read(&mut self, buffer: &mut [u8]) -> IoResult<usize> {
+        let count = self.inner.read(buffer)?;
+
+        if count > 0 {
+            lock(&self.transcript).append(RECEIVED, &buffer[..count])?;
+        }
+
+        Ok(count)
+    }
+}
+
+struct RecordWriter {
+    inner: TransportWrite,
+    transcript: Arc<Mutex<Transcript>>,
+}
+
+impl Write for RecordWriter {
+    fn write(&mut self, buffer: &[u8]) -> IoResult<usize> {
+        // Lock held while writing, so reply can not be recorded before command.
+        let mut transcript = lock(&self.transcript);
+        let count = self.inner.write(buffer)?;
+
+        transcript.append(SENT, &buffer[..count])?;
+
+        Ok(count)
+    }
+
+    fn flush(&mut self) -> IoResult<()> {
+        self.inner.flush()
+    }
+}
+
+#[derive(Debug)]
+enum Entry {
+    Sent(Vec<u8>),
+    Received(Vec<u8>),
+}
+
+#[derive(Debug)]
+struct Replay {
+    entries: Mutex<VecDeque<Entry>>,
+    written: Condvar,
+}
+
+/// Transport replaying transcript written by `RecordTransport`. Received data is returned only
+/// after all data sent before it in transcript written, so replies follow their commands. Data
+/// written differently from transcript is reported with warning.
+#[derive(Debug)]
+pub struct ReplayTransport {
+    replay: Arc<Replay>,
+    read_timeout: Duration,
+}
+
+impl ReplayTransport {
+    pub fn new<R>(transcript: R, read_timeout: Duration) -> Result<Self, IoError>
+    where
+        R: BufRead,
+    {
+        let mut entries = VecDeque::new();
+
+        for line in transcript.lines() {
+            let line = line?;
+            let mut fields = line.split_whitespace().skip(1);
+            let entry = match (fields.next(), fields.next().map(decode_hex)) {
+                (Some(SENT), Some(Ok(data))) => Entry::Sent(data),
+                (Some(RECEIVED), Some(Ok(data))) => Entry::Received(data),
+                (None, _) => continue,
+                _ => {
+                    return Err(IoError::new(
+                        ErrorKind::InvalidData,
+                        format!("Invalid transcript line: {}", line),
+                    ))
+                }
+            };
+
+            entries.push_back(entry);
+        }
+
+        Ok(Self {
+            replay: Arc::new(Replay {
+                entries: Mutex::new(entries),
+                written: Condvar::new(),
+            }),
+            read_timeout,
+        })
+    }
+
+    pub fn open<P>(path: P, read_timeout: Duration) -> Result<Self, IoError>
+    where
+        P: AsRef<Path>,
+    {
+        Self::new(BufReader::new(File::open(path)?), read_timeout)
+    }
+}
+
+impl Transport for ReplayTransport {
+    fn split(self: Box<Self>) -> Result<(TransportRead, TransportWrite), IoError> {
+        Ok((
+            Box::new(ReplayReader {
+                replay: self.replay.clone(),
+                read_timeout: self.read_timeout,
+            }),
+            Box::new(ReplayWriter {
+                replay: self.replay,
+            }),
+        ))
+    }
+}
+
+struct ReplayReader {
+    replay: Arc<Replay>,
+    read_timeout: Duration,
+}
+
+impl Read for ReplayReader {
+    /// Returns `TimedOut` error while waiting for sent data and end of stream when transcript
+    /// finished.
+    fn read(&mut self, buffer: &mut [u8]) -> IoResult<usize> {
+        let entries = lock(&self.replay.entries);
+        let (mut entries, _) = self
+            .replay
+            .written
+            .wait_timeout_while(entries, self.read_timeout, |entries| {
+                matches!(entries.front(), Some(Entry::Sent(_)))
+            })
+            .unwrap_or_else(|error| error.into_inner());
+
+        match entries.front_mut() {
+            None => Ok(0),
+            Some(Entry::Sent(_)) => Err(ErrorKind::TimedOut.into()),
+            Some(Entry::Received(data)) => {
+                let count = buffer.len().min(data.len());
+
+                buffer[..count].copy_from_slice(&data[..count]);
+                data.drain(..count);
+
+                if data.is_empty() {
+                    entries.pop_front();
+                }
+
+                Ok(count)
+            }
+        }
+    }
+}
+
+struct ReplayWriter {
+    replay: Arc<Replay>,
+}
+
+impl Write for ReplayWriter {
+    fn write(&mut self, buffer: &[u8]) -> IoResult<usize> {
+        let mut entries = lock(&self.replay.entries);
+        let mut data = buffer;
+
+        while !data.is_empty() {
+            // Received data recorded before command may be not read yet.
+            let Some((position, expected)) =
+                entries
+                    .iter_mut()
+                    .enumerate()
+                    .find_map(|(position, entry)| match entry {
+                        Entry::Sent(expected) => Some((position, expected)),
+                        Entry::Received(_) => None,
+                    })
+            else {
+                warn!(
+                    "Replay mismatch, unexpected {:?}",
+                    String::from_utf8_lossy(data)
+                );
+
+                break;
+            };
+            let count = data.len().min(expected.len());
+
+            if data[..count] != expected[..count] {
+                warn!(
+                    "Replay mismatch, expected {:?}, written {:?}",
+                    String::from_utf8_lossy(&expected[..count]),
+                    String::from_utf8_lossy(&data[..count])
+                );
+            }
+
+            expected.drain(..count);
+            data = &data[count..];
+
+            if expected.is_empty() {
+                entries.remove(position);
+            }
+        }
+
+        self.replay.written.notify_all();
+
+        Ok(buffer.len())
+    }
+
+    fn flush(&mut self) -> IoResult<()> {
+        Ok(())
+    }
+}
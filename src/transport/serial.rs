@@ -0,0 +1,35 @@
+use serialport::SerialPort;
+use std::io::Error as IoError;
+use std::time::Duration;
+
+use super::Transport;
+use super::TransportRead;
+use super::TransportWrite;
+
+/// Serial port transport, module connected directly to computer.
+#[derive(Debug)]
+pub struct SerialTransport {
+    port: Box<dyn SerialPort>,
+}
+
+impl SerialTransport {
+    pub fn new(port: Box<dyn SerialPort>) -> Self {
+        Self { port }
+    }
+
+    pub fn open(path: &str, baud_rate: u32, read_timeout: Duration) -> Result<Self, IoError> {
+        let port = serialport::new(path, baud_rate)
+            .timeout(read_timeout)
+            .open()?;
+
+        Ok(Self::new(port))
+    }
+}
+
+impl Transport for SerialTransport {
+    fn split(self: Box<Self>) -> Result<(TransportRead, TransportWrite), IoError> {
+        let reader = self.port.try_clone()?;
+
+        Ok((Box::new(reader), Box::new(self.port)))
+    }
+}
@@ -0,0 +1,38 @@
+use std::io::Error as IoError;
+use std::net::TcpStream;
+use std::net::ToSocketAddrs;
+use std::time::Duration;
+
+use super::Transport;
+use super::TransportRead;
+use super::TransportWrite;
+
+/// Raw TCP socket transport, module connected to remote serial port server (like `ser2net`).
+#[derive(Debug)]
+pub struct TcpTransport {
+    stream: TcpStream,
+}
+
+impl TcpTransport {
+    pub fn new(stream: TcpStream, read_timeout: Duration) -> Result<Self, IoError> {
+        stream.set_read_timeout(Some(read_timeout))?;
+        stream.set_nodelay(true)?;
+
+        Ok(Self { stream })
+    }
+
+    pub fn connect<A>(address: A, read_timeout: Duration) -> Result<Self, IoError>
+    where
+        A: ToSocketAddrs,
+    {
+        Self::new(TcpStream::connect(address)?, read_timeout)
+    }
+}
+
+impl Transport for TcpTransport {
+    fn split(self: Box<Self>) -> Result<(TransportRead, TransportWrite), IoError> {
+        let reader = self.stream.try_clone()?;
+
+        Ok((Box::new(reader), Box::new(self.stream)))
+    }
+}
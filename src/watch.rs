@@ -0,0 +1,161 @@
+use log::warn;
+use sim800_reader::concat::reassemble;
+use sim800_reader::concat::SmsMessage;
+use sim800_reader::parser::Response;
+use sim800_reader::parser::SmsMessageStorage;
+use sim800_reader::sim800::Sim800;
+use std::error::Error;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::mpsc::RecvTimeoutError;
+use std::time::Duration;
+
+/// Interval to check stop flag while waiting for new messages.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Reads message with given index, result converted to list response to reassemble parts.
+pub fn read_message(sim800: &mut Sim800, index: u16) -> Result<Option<Response>, Box<dyn Error>> {
+    match sim800.send_one(&format!(r#"AT+CMGR={}"#, index))? {
+        Some(Response::ReadSmsMessage {
+            status,
+            address,
+            address_text,
+            timestamp,
+            text,
+            pdu,
+        }) => Ok(Some(Response::ListSmsMessage {
+            index,
+            status,
+            address,
+            address_text,
+            timestamp,
+            text,
+            pdu,
+        })),
+        _ => Ok(None),
+    }
+}
+
+/// Returns storage used to read messages, `None` if module does not report it.
+pub fn preferred_storage(sim800: &mut Sim800) -> Option<SmsMessageStorage> {
+    match sim800.send_one(r#"AT+CPMS?"#) {
+        Ok(Some(Response::PreferredStorage { storage, .. })) => Some(storage),
+        _ => None,
+    }
+}
+
+/// Waits for new message indications until `running` flag cleared. Every complete message passed
+/// to `handle` with its storage and removed from module storage if `delete` set, parts of
+/// concatenated messages kept until all parts received. Messages indicated in other storage than
+/// read one skipped with warning, `AT+CMGR` and `AT+CMGD` would use wrong message.
+pub fn watch<F>(
+    sim800: &mut Sim800,
+    running: &AtomicBool,
+    delete: bool,
+    mut handle: F,
+) -> Result<(), Box<dyn Error>>
+where
+    F: FnMut(&SmsMessage, &SmsMessageStorage) -> Result<(), Box<dyn Error>>,
+{
+    let unsolicited = sim800.subscribe();
+    let mut parts = Vec::new();
+    let read_storage = preferred_storage(sim800);
+
+    sim800.send(r#"AT+CNMI=2,1,0,0,0"#)?;
+
+    while running.load(Ordering::Relaxed) {
+        let (storage, index) = match unsolicited.recv_timeout(POLL_INTERVAL) {
+            Ok(Response::NewSmsMessage { storage, index }) => (storage, index),
+            Ok(_) | Err(RecvTimeoutError::Timeout) => continue,
+            Err(RecvTimeoutError::Disconnected) => break,
+        };
+
+        if let Some(read_storage) = read_storage.as_ref().filter(|&read| *read != storage) {
+            warn!(
+                "Message {} in storage {} skipped, messages read from {}",
+                index, storage, read_storage
+            );
+
+            continue;
+        }
+
+        if let Some(response) = read_message(sim800, index)? {
+            parts.push(response);
+        }
+
+        for message in reassemble(parts.clone()) {
+            if !message.is_complete() {
+                continue;
+            }
+
+            handle(&message, &storage)?;
+
+            parts.retain(|part| match part {
+                Response::ListSmsMessage { index, .. } => !message.indices.contains(index),
+                _ => false,
+            });
+
+            if delete {
+                for index in message.indices {
+                    sim800.send(&format!(r#"AT+CMGD={},0"#, index))?;
+                }
+            }
+        }
+    }
+
+    sim800.send(r#"AT+CNMI=0,0,0,0,0"#)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sim800_reader::parser::SmsMessageStatus;
+    use sim800_reader::simulator::Simulator;
+    use sim800_reader::transport::PipeTransport;
+    use std::thread;
+    use time::macros::datetime;
+
+    #[test]
+    fn skips_message_in_other_storage() {
+        let timestamp = datetime!(2023-12-31 23:30:00 +2);
+        let mut simulator = Simulator::new();
+
+        simulator.add_message(SmsMessageStatus::ReceivedRead, "+123", timestamp, "old");
+
+        let (host, module) = PipeTransport::pair(Duration::from_millis(50));
+        let handle = simulator.spawn(Box::new(module)).unwrap();
+        let mut sim800 = Sim800::new(Box::new(host)).unwrap();
+        let running = AtomicBool::new(true);
+        let mut handled = Vec::new();
+
+        thread::scope(|scope| {
+            scope.spawn(|| {
+                // Indications sent before watch subscribes would be lost.
+                thread::sleep(Duration::from_millis(300));
+
+                // Index 1 in SIM storage holds other message.
+                handle.emit(r#"+CMTI: "ME",1"#).unwrap();
+                handle.receive_message("+456", timestamp, "new").unwrap();
+            });
+
+            watch(&mut sim800, &running, true, |message, storage| {
+                handled.push((message.text.clone(), storage.clone()));
+                running.store(false, Ordering::Relaxed);
+
+                Ok(())
+            })
+            .unwrap();
+        });
+
+        assert_eq!(handled, [("new".to_string(), SmsMessageStorage::Sim)]);
+        assert_eq!(
+            handle.simulator().messages().keys().collect::<Vec<_>>(),
+            [&1]
+        );
+
+        sim800.join().unwrap();
+        handle.join().unwrap();
+    }
+}
@@ -0,0 +1,147 @@
+use sim800_reader::parser::Response;
+use sim800_reader::parser::SmsMessageStatus;
+use sim800_reader::pdu::encode_hex;
+use sim800_reader::sim800::Sim800;
+use sim800_reader::sim800::Sim800Error;
+use sim800_reader::simulator::Simulator;
+use sim800_reader::transport::PipeTransport;
+use sim800_reader::transport::RecordTransport;
+use sim800_reader::transport::ReplayTransport;
+use std::io::Cursor;
+use std::io::Result as IoResult;
+use std::io::Write;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::Instant;
+use time::macros::datetime;
+
+const READ_TIMEOUT: Duration = Duration::from_millis(50);
+
+/// Transcript output shared with test.
+#[derive(Debug, Clone, Default)]
+struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+impl Write for Buffer {
+    fn write(&mut self, buffer: &[u8]) -> IoResult<usize> {
+        self.0.lock().unwrap().extend_from_slice(buffer);
+
+        Ok(buffer.len())
+    }
+
+    fn flush(&mut self) -> IoResult<()> {
+        Ok(())
+    }
+}
+
+/// Runs commands covering single line, list and prompt replies, returns parsed responses.
+fn session(sim800: &mut Sim800) -> Vec<String> {
+    let mut result = Vec::new();
+
+    for command in [
+        "AT+CSQ",
+        "AT+CMGF=1",
+        "AT+CSDH=1",
+        r#"AT+CMGL="ALL",1"#,
+        "AT+CMGR=1",
+    ] {
+        result.push(format!("{:?}", sim800.send_list(command).unwrap()));
+    }
+
+    result.push(format!("{:?}", sim800.send_sms("+123", "hello").unwrap()));
+    result
+}
+
+fn transcript(lines: &[(&str, &str)]) -> Cursor<String> {
+    Cursor::new(
+        lines
+            .iter()
+            .enumerate()
+            .map(|(time, (direction, data))| {
+                format!("{} {} {}\n", time, direction, encode_hex(data.as_bytes()))
+            })
+            .collect(),
+    )
+}
+
+#[test]
+fn replays_recorded_session() {
+    let mut simulator = Simulator::new();
+
+    simulator.add_message(
+        SmsMessageStatus::ReceivedUnread,
+        "+456",
+        datetime!(2023-12-31 23:30:00 +2),
+        "hello",
+    );
+
+    let buffer = Buffer::default();
+    let (host, module) = PipeTransport::pair(READ_TIMEOUT);
+    let handle = simulator.spawn(Box::new(module)).unwrap();
+    let record = RecordTransport::new(Box::new(host), Box::new(buffer.clone()));
+    let mut sim800 = Sim800::new(Box::new(record)).unwrap();
+    let recorded = session(&mut sim800);
+
+    assert!(recorded[3].contains("ListSmsMessage"), "{:?}", recorded);
+    assert_eq!(recorded[5], "Some(0)");
+
+    sim800.join().unwrap();
+    handle.join().unwrap();
+
+    let data = buffer.0.lock().unwrap().clone();
+    let replay = ReplayTransport::new(Cursor::new(data), READ_TIMEOUT).unwrap();
+    let mut sim800 = Sim800::new(Box::new(replay)).unwrap();
+
+    assert_eq!(session(&mut sim800), recorded);
+
+    sim800.join().unwrap();
+}
+
+#[test]
+fn replays_reply_to_mismatched_command() {
+    let replay = ReplayTransport::new(
+        transcript(&[
+            (">", "AT+CSQ\r\n"),
+            ("<", "\r\n+CSQ: 20,0\r\n\r\nOK\r\n"),
+            (">", "AT\r\n"),
+        ]),
+        READ_TIMEOUT,
+    )
+    .unwrap();
+    let mut sim800 = Sim800::new(Box::new(replay)).unwrap();
+
+    // Mismatch only reported with warning, recorded reply still returned.
+    assert!(matches!(
+        sim800.send_one("AT+CPAS").unwrap(),
+        Some(Response::SignalQuality { .. })
+    ));
+
+    sim800.join().unwrap();
+}
+
+#[test]
+fn fails_pending_command_at_end_of_transcript() {
+    let replay = ReplayTransport::new(
+        transcript(&[(">", "AT\r\n"), ("<", "\r\nOK\r\n"), (">", "AT+CSQ\r\n")]),
+        READ_TIMEOUT,
+    )
+    .unwrap();
+    let mut sim800 = Sim800::new(Box::new(replay)).unwrap();
+
+    sim800.send("AT").unwrap();
+
+    // Reader gets end of stream once command written, command fails without waiting for
+    // timeout.
+    let start = Instant::now();
+
+    assert!(matches!(
+        sim800.send("AT+CSQ"),
+        Err(Sim800Error::RecvError(_))
+    ));
+    assert!(start.elapsed() < Duration::from_secs(5));
+
+    // Commands written past end of transcript are dropped.
+    assert!(sim800.send("AT").is_err());
+
+    sim800.join().unwrap();
+}
@@ -0,0 +1,382 @@
+use sim800_reader::error_code::ServiceError;
+use sim800_reader::parser::MobileEquipmentError;
+use sim800_reader::parser::Response;
+use sim800_reader::parser::SmsMessageStatus;
+use sim800_reader::sim800::Received;
+use sim800_reader::sim800::Sim800;
+use sim800_reader::sim800::Sim800Error;
+use sim800_reader::simulator::Fault;
+use sim800_reader::simulator::Simulator;
+use sim800_reader::simulator::SimulatorHandle;
+use sim800_reader::transport::PipeTransport;
+use std::time::Duration;
+use time::macros::datetime;
+use time::OffsetDateTime;
+
+const READ_TIMEOUT: Duration = Duration::from_millis(50);
+
+const TIMESTAMP: OffsetDateTime = datetime!(2023-12-31 23:30:00 +2);
+
+fn connect(simulator: Simulator) -> (Sim800, SimulatorHandle) {
+    let (host, module) = PipeTransport::pair(READ_TIMEOUT);
+    let handle = simulator.spawn(Box::new(module)).unwrap();
+    let sim800 = Sim800::new(Box::new(host)).unwrap();
+
+    (sim800, handle)
+}
+
+fn disconnect(sim800: Sim800, handle: SimulatorHandle) {
+    sim800.join().unwrap();
+    handle.join().unwrap();
+}
+
+fn with_messages() -> Simulator {
+    let mut simulator = Simulator::new();
+
+    simulator.add_message(SmsMessageStatus::ReceivedRead, "+123", TIMESTAMP, "hello");
+    simulator.add_message(
+        SmsMessageStatus::ReceivedUnread,
+        "+456",
+        TIMESTAMP,
+        "> quoted",
+    );
+
+    simulator
+}
+
+/// Returns index, address and text of listed messages.
+fn listed(responses: &[Response]) -> Vec<(u16, String, String)> {
+    responses
+        .iter()
+        .filter_map(|response| match response {
+            Response::ListSmsMessage {
+                index,
+                address,
+                text,
+                ..
+            } => Some((*index, address.to_string(), text.clone())),
+            _ => None,
+        })
+        .collect()
+}
+
+fn expected_list() -> Vec<(u16, String, String)> {
+    vec![
+        (1, "+123".into(), "hello".into()),
+        (2, "+456".into(), "> quoted".into()),
+    ]
+}
+
+#[test]
+fn lists_messages_in_pdu_mode() {
+    let (mut sim800, handle) = connect(with_messages());
+
+    sim800.set_pdu_mode(true).unwrap();
+
+    let responses = sim800.send_list("AT+CMGL=4").unwrap();
+
+    assert_eq!(listed(&responses), expected_list());
+    assert_eq!(
+        handle.simulator().messages()[&2].status,
+        SmsMessageStatus::ReceivedRead
+    );
+    assert!(matches!(
+        sim800.send_list(r#"AT+CMGL="ALL""#),
+        Err(Sim800Error::Modem(_))
+    ));
+
+    disconnect(sim800, handle);
+}
+
+#[test]
+fn lists_messages_in_text_mode() {
+    let (mut sim800, handle) = connect(with_messages());
+
+    sim800.set_pdu_mode(false).unwrap();
+    sim800.send("AT+CSDH=1").unwrap();
+
+    let responses = sim800.send_list(r#"AT+CMGL="ALL",1"#).unwrap();
+
+    assert_eq!(listed(&responses), expected_list());
+    assert_eq!(
+        handle.simulator().messages()[&2].status,
+        SmsMessageStatus::ReceivedUnread
+    );
+    assert!(matches!(
+        sim800.send_list("AT+CMGL=4"),
+        Err(Sim800Error::Modem(_))
+    ));
+
+    disconnect(sim800, handle);
+}
+
+#[test]
+fn reads_message() {
+    let (mut sim800, handle) = connect(with_messages());
+
+    let response = sim800.send_one("AT+CMGR=2").unwrap();
+
+    match response {
+        Some(Response::ReadSmsMessage {
+            status,
+            address,
+            text,
+            ..
+        }) => {
+            assert_eq!(status, SmsMessageStatus::ReceivedUnread);
+            assert_eq!(address.to_string(), "+456");
+            assert_eq!(text, "> quoted");
+        }
+        response => panic!("Unexpected response {:?}", response),
+    }
+
+    assert_eq!(
+        handle.simulator().messages()[&2].status,
+        SmsMessageStatus::ReceivedRead
+    );
+    assert!(sim800.send_one("AT+CMGR=3").unwrap().is_none());
+
+    disconnect(sim800, handle);
+}
+
+#[test]
+fn deletes_message() {
+    let (mut sim800, handle) = connect(with_messages());
+
+    sim800.send("AT+CMGD=1").unwrap();
+
+    let responses = sim800.send_list("AT+CMGL=4").unwrap();
+
+    assert_eq!(listed(&responses), expected_list()[1..]);
+    assert_eq!(
+        handle.simulator().messages().keys().collect::<Vec<_>>(),
+        [&2]
+    );
+
+    disconnect(sim800, handle);
+}
+
+#[test]
+fn sends_text_in_pdu_mode() {
+    let (mut sim800, handle) = connect(Simulator::new());
+
+    sim800.set_pdu_mode(true).unwrap();
+
+    let text = "x".repeat(200);
+    let references = sim800.send_text("+123", &text).unwrap();
+
+    assert_eq!(references, [0, 1]);
+
+    let sent: Vec<_> = handle
+        .simulator()
+        .sent()
+        .iter()
+        .map(|message| (message.address.to_string(), message.text.clone()))
+        .collect();
+
+    assert_eq!(sent.len(), 2);
+    assert!(sent.iter().all(|(address, _)| address == "+123"));
+    assert_eq!(
+        sent.iter()
+            .map(|(_, text)| text.as_str())
+            .collect::<String>(),
+        text
+    );
+
+    disconnect(sim800, handle);
+}
+
+#[test]
+fn fails_text_part_rejected_by_module() {
+    let (mut sim800, handle) = connect(Simulator::new());
+
+    sim800.send("AT+CMEE=2").unwrap();
+    handle.simulator().set_registered(false);
+
+    assert!(matches!(
+        sim800.send_text("+123", &"x".repeat(200)),
+        Err(Sim800Error::Modem(MobileEquipmentError::Service(_)))
+    ));
+    assert!(handle.simulator().sent().is_empty());
+
+    disconnect(sim800, handle);
+}
+
+#[test]
+fn sends_sms_in_text_mode() {
+    let (mut sim800, handle) = connect(Simulator::new());
+
+    sim800.set_pdu_mode(false).unwrap();
+
+    assert_eq!(sim800.send_sms("+123", "hello").unwrap(), Some(0));
+    assert_eq!(handle.simulator().sent()[0].text, "hello");
+
+    disconnect(sim800, handle);
+}
+
+#[test]
+fn delivers_unsolicited_codes() {
+    let (mut sim800, handle) = connect(Simulator::new());
+    let codes = sim800.subscribe();
+
+    handle.receive_message("+123", TIMESTAMP, "hello").unwrap();
+    handle.emit("RING").unwrap();
+
+    // Codes received between commands do not replace replies.
+    assert!(matches!(
+        sim800.send_one("AT+CSQ").unwrap(),
+        Some(Response::SignalQuality { .. })
+    ));
+    assert!(matches!(
+        codes.recv_timeout(Duration::from_secs(1)),
+        Ok(Response::NewSmsMessage { index: 1, .. })
+    ));
+    assert!(matches!(
+        codes.recv_timeout(Duration::from_secs(1)),
+        Ok(Response::Ring)
+    ));
+
+    disconnect(sim800, handle);
+}
+
+#[test]
+fn delivers_power_on_codes() {
+    let (mut sim800, handle) = connect(Simulator::new());
+    let codes = sim800.subscribe();
+
+    for line in ["RDY", "+CFUN: 1", "+CPIN: READY"] {
+        handle.emit(line).unwrap();
+    }
+
+    assert!(matches!(
+        sim800.send_one("AT+CSQ").unwrap(),
+        Some(Response::SignalQuality { .. })
+    ));
+    assert!(matches!(
+        sim800.send_one("AT+CPIN?").unwrap(),
+        Some(Response::PinStatus { .. })
+    ));
+    assert!(matches!(
+        sim800.send_one("AT+CCALR?").unwrap(),
+        Some(Response::Unknown { prefix: Some(prefix), .. }) if prefix == "+CCALR"
+    ));
+
+    let codes: Vec<_> = codes.try_iter().collect();
+
+    assert!(
+        matches!(
+            codes.as_slice(),
+            [
+                Response::Unknown { prefix: None, .. },
+                Response::Unknown {
+                    prefix: Some(_),
+                    ..
+                },
+                Response::PinStatus { .. }
+            ]
+        ),
+        "{:?}",
+        codes
+    );
+
+    disconnect(sim800, handle);
+}
+
+#[test]
+fn times_out_without_reply() {
+    let (mut sim800, handle) = connect(Simulator::new());
+
+    sim800.set_timeout(Duration::from_millis(300));
+    handle.simulator().inject_fault(Fault::Timeout);
+
+    assert!(matches!(sim800.send("AT"), Err(Sim800Error::Timeout(_))));
+    assert!(sim800.send("AT").is_ok());
+
+    disconnect(sim800, handle);
+}
+
+#[test]
+fn reports_service_error() {
+    let (mut sim800, handle) = connect(Simulator::new());
+
+    sim800.send("AT+CMEE=2").unwrap();
+    handle.simulator().inject_fault(Fault::ServiceError(302));
+
+    assert!(matches!(
+        sim800.send("AT"),
+        Err(Sim800Error::Modem(MobileEquipmentError::Service(
+            ServiceError::OperationNotAllowed
+        )))
+    ));
+    assert!(sim800.send("AT").is_ok());
+
+    disconnect(sim800, handle);
+}
+
+#[test]
+fn skips_garbled_line() {
+    let (mut sim800, handle) = connect(with_messages());
+
+    handle.simulator().inject_fault(Fault::Garbled);
+
+    let responses = sim800.send_list("AT+CMGL=4").unwrap();
+
+    assert_eq!(listed(&responses), expected_list());
+
+    disconnect(sim800, handle);
+}
+
+/// Runs commands with module echo enabled or disabled, no echoed line may reach parser as
+/// unrecognized text.
+fn run_with_echo(enabled: bool) {
+    let (mut sim800, handle) = connect(with_messages());
+    let received = sim800.monitor();
+
+    sim800.set_echo(enabled).unwrap();
+
+    assert!(matches!(
+        sim800.send_one("AT+CSQ").unwrap(),
+        Some(Response::SignalQuality { .. })
+    ));
+    assert_eq!(
+        listed(&sim800.send_list("AT+CMGL=4").unwrap()),
+        expected_list()
+    );
+
+    sim800.set_pdu_mode(false).unwrap();
+
+    assert_eq!(sim800.send_sms("+123", "hello").unwrap(), Some(0));
+
+    sim800.set_pdu_mode(true).unwrap();
+
+    assert_eq!(sim800.send_text("+456", "world").unwrap(), [1]);
+    assert_eq!(handle.simulator().sent().len(), 2);
+
+    disconnect(sim800, handle);
+
+    let unrecognized: Vec<_> = received
+        .try_iter()
+        .filter(|received| {
+            matches!(
+                received,
+                Received::Unparsed { .. }
+                    | Received::Parsed {
+                        response: Response::Unknown { .. },
+                        ..
+                    }
+            )
+        })
+        .collect();
+
+    assert!(unrecognized.is_empty(), "{:?}", unrecognized);
+}
+
+#[test]
+fn strips_command_echo() {
+    run_with_echo(true);
+}
+
+#[test]
+fn works_without_command_echo() {
+    run_with_echo(false);
+}